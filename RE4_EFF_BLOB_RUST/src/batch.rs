@@ -0,0 +1,135 @@
+use re4_effblob::EffError;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::Instant;
+
+/// Files at any depth under `root` accepted by `matches`, sorted so the summary
+/// always comes in the same order. Symbolic links to folders are not followed.
+pub fn find(root: &Path, matches: &dyn Fn(&Path) -> bool) -> Result<Vec<PathBuf>, EffError> {
+    let mut found = Vec::new();
+    let mut folders = vec![root.to_path_buf()];
+    while let Some(folder) = folders.pop() {
+        let io = |error| EffError::Io {
+            path: Some(folder.clone()),
+            source: error,
+        };
+        for entry in std::fs::read_dir(&folder).map_err(io)? {
+            let entry = entry.map_err(io)?;
+            let path = entry.path();
+            // file_type nao segue links: um link para uma pasta acima entraria em loop,
+            // entao links para pastas sao ignorados; links para arquivos valem
+            if entry.file_type().map_err(io)?.is_dir() {
+                folders.push(path);
+            } else if !path.is_dir() && matches(&path) {
+                found.push(path);
+            }
+        }
+    }
+    found.sort();
+    Ok(found)
+}
+
+pub fn default_jobs() -> usize {
+    thread::available_parallelism().map_or(1, |jobs| jobs.get())
+}
+
+// resultado de um arquivo: as mensagens e false quando terminou com diferencas (verify)
+type Report = (Vec<String>, Result<bool, EffError>);
+
+/// Runs `work` for each file on `jobs` threads, then prints the messages of each file,
+/// in order, followed by its result and a total. Returns `false` when a file failed.
+pub fn run<F>(files: &[PathBuf], jobs: usize, work: F) -> bool
+where
+    F: Fn(&Path, &mut Vec<String>) -> Result<bool, EffError> + Sync,
+{
+    if files.is_empty() {
+        println!("# No files found");
+        return false;
+    }
+    let start = Instant::now();
+    let next = AtomicUsize::new(0);
+
+    let mut reports: Vec<Option<Report>> = (0..files.len()).map(|_| None).collect();
+    thread::scope(|scope| {
+        let workers: Vec<_> = (0..jobs.clamp(1, files.len().max(1)))
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut done = Vec::new();
+                    loop {
+                        let index = next.fetch_add(1, Ordering::Relaxed);
+                        let Some(file) = files.get(index) else { break };
+                        let mut log = Vec::new();
+                        let result = work(file, &mut log);
+                        done.push((index, (log, result)));
+                    }
+                    done
+                })
+            })
+            .collect();
+        for worker in workers {
+            // uma thread que parou no meio deixa os arquivos dela sem resultado
+            for (index, report) in worker.join().unwrap_or_default() {
+                reports[index] = Some(report);
+            }
+        }
+    });
+
+    let mut failed = 0;
+    for (file, report) in files.iter().zip(reports) {
+        match report {
+            Some((log, result)) => {
+                for line in log {
+                    println!("{}", line);
+                }
+                match result {
+                    Ok(true) => println!("# OK: {}", file.display()),
+                    Ok(false) => {
+                        failed += 1;
+                        println!("# Failed: {}", file.display());
+                    }
+                    Err(error) => {
+                        failed += 1;
+                        println!("# Failed: {}", file.display());
+                        println!("# Error: {}", error);
+                    }
+                }
+            }
+            None => {
+                failed += 1;
+                println!("# Failed: {}: interrupted", file.display());
+            }
+        }
+    }
+
+    println!(
+        "# {} files: {} ok, {} failed ({:.1} s)",
+        files.len(),
+        files.len() - failed,
+        failed,
+        start.elapsed().as_secs_f64()
+    );
+    failed == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    #[test]
+    fn find_skips_folder_links() {
+        let root = std::env::temp_dir().join(format!("re4_effblob_find_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(root.join("a/b")).unwrap();
+        std::fs::write(root.join("a/b/core.EFFBLOB"), []).unwrap();
+        std::fs::write(root.join("a/other.txt"), []).unwrap();
+        // link para a propria raiz, o loop que travava a busca
+        std::os::unix::fs::symlink(&root, root.join("a/b/loop")).unwrap();
+        std::os::unix::fs::symlink(root.join("a/b/core.EFFBLOB"), root.join("link.EFFBLOB")).unwrap();
+
+        let found = find(&root, &|path| path.extension().is_some_and(|extension| extension == "EFFBLOB"));
+        let _ = std::fs::remove_dir_all(&root);
+        assert_eq!(found.unwrap(), [root.join("a/b/core.EFFBLOB"), root.join("link.EFFBLOB")]);
+    }
+}
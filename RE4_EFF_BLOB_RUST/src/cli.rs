@@ -0,0 +1,472 @@
+use crate::batch;
+use clap::{Parser, Subcommand, ValueEnum};
+use re4_effblob::diff::Tolerance;
+use re4_effblob::{manifest_path, Manifest};
+use re4_effblob::merge::Side;
+use re4_effblob::paths::DEFAULT_SAMPLES;
+use re4_effblob::{EffError, Platform};
+use re4_effblob::Endian;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+#[derive(Parser)]
+#[command(
+    name = "RE4_EFFBLOB_RUST",
+    version,
+    about = "Extract and repack RE4 EFFBLOB/EFFBLOBBIG files",
+    after_help = "The old form of the arguments (-e, -b, -v, -j, -csv, ...) used by the .bat files is still accepted."
+)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+    /// Files processed at the same time when a folder is given [default: number of cores]
+    #[arg(short, long, global = true)]
+    jobs: Option<usize>,
+}
+
+#[derive(Clone, Copy, Default, ValueEnum)]
+enum EndianArg {
+    /// Detected from the file header, or read from the .EFFBLOBTXT
+    #[default]
+    Auto,
+    /// .EFFBLOB (2007, PS2, UHD, PS4, NS)
+    Little,
+    /// .EFFBLOBBIG (GC, Wii, X360)
+    Big,
+}
+
+impl EndianArg {
+    fn endianness(self) -> Option<Endian<()>> {
+        match self {
+            EndianArg::Auto => None,
+            EndianArg::Little => Some(Endian::Little(())),
+            EndianArg::Big => Some(Endian::Big(())),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Default, ValueEnum)]
+enum Format {
+    /// txt2 folder and .EFFBLOBTXT
+    #[default]
+    Text,
+    /// One JSON document
+    Json,
+    /// "Effect 0.csv" and "Effect 1.csv" spreadsheets
+    Csv,
+    /// glTF 2.0 scene (.gltf + .bin) of the effects and paths, for viewing in Blender
+    Gltf,
+}
+
+#[derive(Clone, Copy, Default, ValueEnum)]
+enum Prefer {
+    #[default]
+    Ours,
+    Theirs,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Extract a .EFFBLOB/.EFFBLOBBIG to a txt2 folder, JSON, CSV or glTF
+    Extract {
+        /// File, or folder searched recursively for .EFFBLOB/.EFFBLOBBIG files
+        file: PathBuf,
+        /// Output folder or file [default: the file name without extension];
+        /// for a folder input, the root where its subfolders are recreated
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        /// Byte order of the file
+        #[arg(short, long, value_enum, default_value_t)]
+        endian: EndianArg,
+        /// Release of the game: 2007, PS2, UHD, PS4, NS (little endian) or GC, Wii, X360 (big endian);
+        /// recorded in the .EFFBLOBTXT and used by repack
+        #[arg(long)]
+        platform: Option<Platform>,
+        #[arg(short, long, value_enum, default_value_t)]
+        format: Format,
+    },
+    /// Repack a txt2 folder (or its .EFFBLOBTXT) or a JSON file
+    Repack {
+        /// Extracted folder, .EFFBLOBTXT or JSON file, or a folder searched recursively
+        /// for extracted folders
+        input: PathBuf,
+        /// Output file [default: the input name with .EFFBLOB or .EFFBLOBBIG];
+        /// for a folder input, the root where its subfolders are recreated
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        /// Byte order of the output; auto = the .EFFBLOBTXT order, for JSON the output extension
+        #[arg(short, long, value_enum, default_value_t)]
+        endian: EndianArg,
+        /// Release of the game: 2007, PS2, UHD, PS4, NS (little endian) or GC, Wii, X360 (big endian);
+        /// auto = the one recorded in the .EFFBLOBTXT
+        #[arg(long)]
+        platform: Option<Platform>,
+        /// Write the file even when the validation finds errors
+        #[arg(long)]
+        no_validate: bool,
+        /// Copy Table 5 and Table 10 unchanged when the byte order changes; they are raw bytes,
+        /// not researched yet, so they cannot be converted
+        #[arg(long)]
+        copy_raw: bool,
+    },
+    /// Apply the CSV spreadsheets of a folder over a .EFFBLOB/.EFFBLOBBIG
+    ApplyCsv {
+        file: PathBuf,
+        /// Folder with "Effect 0.csv" and/or "Effect 1.csv"
+        csv: PathBuf,
+        /// Output file, in the byte order of the input
+        #[arg(short, long)]
+        output: PathBuf,
+        /// Write the file even when the validation finds errors
+        #[arg(long)]
+        no_validate: bool,
+    },
+    /// Show the sections and the table, effect and path counts of a .EFFBLOB/.EFFBLOBBIG
+    Info {
+        file: PathBuf,
+        #[arg(short, long, value_enum, default_value_t)]
+        endian: EndianArg,
+        /// Print a JSON document instead of text
+        #[arg(long)]
+        json: bool,
+    },
+    /// Check that extract + repack rebuilds the files byte by byte
+    Verify {
+        /// Files, or folders searched recursively for .EFFBLOB/.EFFBLOBBIG files
+        #[arg(required = true)]
+        files: Vec<PathBuf>,
+        #[arg(short, long, value_enum, default_value_t)]
+        endian: EndianArg,
+    },
+    /// Check the references between the tables (textures, paths, groups); repack, apply-csv
+    /// and convert do the same check before writing
+    Validate {
+        /// Files, extracted folders, .EFFBLOBTXT or JSON files, or folders searched
+        /// recursively for .EFFBLOB/.EFFBLOBBIG files
+        #[arg(required = true)]
+        files: Vec<PathBuf>,
+        #[arg(short, long, value_enum, default_value_t)]
+        endian: EndianArg,
+        /// Release of the game: 2007, PS2, UHD, PS4, NS (little endian) or GC, Wii, X360 (big endian)
+        #[arg(long)]
+        platform: Option<Platform>,
+    },
+    /// Compare two files field by field; exits with 1 when they differ
+    Diff {
+        /// .EFFBLOB/.EFFBLOBBIG, extracted folder, .EFFBLOBTXT or JSON file
+        old: PathBuf,
+        new: PathBuf,
+        #[arg(short, long, value_enum, default_value_t)]
+        endian: EndianArg,
+        /// Floats that differ by up to this value are equal
+        #[arg(long, default_value_t = 0.0)]
+        tolerance: f64,
+        /// Floats that differ by up to this fraction of the larger value are equal (0.01 = 1%)
+        #[arg(long, default_value_t = 0.0)]
+        relative: f64,
+        /// Print a JSON document instead of text
+        #[arg(long)]
+        json: bool,
+    },
+    /// Combine the changes of two mods made over the same file, field by field
+    Merge {
+        /// The original file both mods started from
+        base: PathBuf,
+        ours: PathBuf,
+        theirs: PathBuf,
+        /// Output .EFFBLOB, .EFFBLOBBIG or .json
+        #[arg(short, long)]
+        output: PathBuf,
+        /// Byte order of the output; auto = by the output extension
+        #[arg(short, long, value_enum, default_value_t)]
+        endian: EndianArg,
+        /// Version kept when both mods changed the same value
+        #[arg(long, value_enum, default_value_t)]
+        prefer: Prefer,
+        /// Conflict report, text or .json [default: the output name + .conflicts.txt]
+        #[arg(long)]
+        report: Option<PathBuf>,
+        /// Write the file even when the validation finds errors
+        #[arg(long)]
+        no_validate: bool,
+    },
+    /// Rewrite a .EFFBLOB as .EFFBLOBBIG or the other way around
+    Convert {
+        /// File, or folder searched recursively for .EFFBLOB/.EFFBLOBBIG files
+        file: PathBuf,
+        /// Output file or folder [default: the input name with the new extension];
+        /// for a folder input, the root where its subfolders are recreated
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        /// Byte order of the input
+        #[arg(long, value_enum, default_value_t)]
+        from: EndianArg,
+        /// Byte order of the output; auto = the one of --platform, or the opposite of the input
+        #[arg(short, long, value_enum, default_value_t)]
+        endian: EndianArg,
+        /// Release the output is for: 2007, PS2, UHD, PS4, NS (little endian) or GC, Wii, X360 (big endian)
+        #[arg(long)]
+        platform: Option<Platform>,
+        /// Write the file even when the validation finds errors
+        #[arg(long)]
+        no_validate: bool,
+        /// Copy Table 5 and Table 10 unchanged when the byte order changes; they are raw bytes,
+        /// not researched yet, so they cannot be converted
+        #[arg(long)]
+        copy_raw: bool,
+    },
+    /// Export the paths of Table 9 as OBJ lines and an SVG with top, side and front views
+    Paths {
+        /// .EFFBLOB/.EFFBLOBBIG, extracted folder, .EFFBLOBTXT or JSON file
+        file: PathBuf,
+        /// Output .obj or .svg, or a name for both [default: the input name + "_paths"]
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        #[arg(short, long, value_enum, default_value_t)]
+        endian: EndianArg,
+        /// Points evaluated on each Bezier segment for the OBJ
+        #[arg(long, default_value_t = DEFAULT_SAMPLES)]
+        samples: usize,
+    },
+    /// Write the JSON Schema of the JSON documents
+    Schema {
+        /// Output file
+        #[arg(short, long, default_value = "eff.schema.json")]
+        output: PathBuf,
+    },
+}
+
+fn has_extension(path: &Path, extension: &str) -> bool {
+    path.extension().is_some_and(|found| found.eq_ignore_ascii_case(extension))
+}
+
+fn is_effblob(path: &Path) -> bool {
+    has_extension(path, "EFFBLOB") || has_extension(path, "EFFBLOBBIG")
+}
+
+// pasta extraida: tem o .EFFBLOBTXT ao lado, ou as pastas do write_to_text (versoes antigas)
+fn is_extracted(path: &Path) -> bool {
+    manifest_path(path).is_file() || ["Tables", "Effect 0", "Effect 1"].iter().any(|folder| path.join(folder).is_dir())
+}
+
+// saida de um arquivo encontrado dentro de root: ao lado dele, ou no mesmo caminho dentro de output
+fn mirror(root: &Path, file: &Path, output: Option<&Path>) -> Result<PathBuf, EffError> {
+    let Some(output) = output else { return Ok(file.to_path_buf()) };
+    let path = output.join(file.strip_prefix(root).unwrap_or(file));
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|error| EffError::Io {
+            path: Some(parent.into()),
+            source: error,
+        })?;
+    }
+    Ok(path)
+}
+
+// mostra as mensagens de um comando com um arquivo so
+fn single<T>(work: impl FnOnce(&mut Vec<String>) -> Result<T, EffError>) -> Result<T, EffError> {
+    let mut log = Vec::new();
+    let result = work(&mut log);
+    for line in log {
+        println!("{}", line);
+    }
+    result
+}
+
+fn extract(
+    file: &Path,
+    output: &Path,
+    endianness: Option<Endian<()>>,
+    platform: Option<Platform>,
+    format: Format,
+) -> Result<(), EffError> {
+    match format {
+        Format::Text => crate::extract(file, &output.with_extension(""), endianness, platform),
+        Format::Json => crate::extract_json(file, &output.with_extension("json"), endianness, platform),
+        Format::Gltf => crate::extract_gltf(file, &output.with_extension("gltf"), endianness, platform),
+        Format::Csv => {
+            crate::extract_csv(file, &crate::with_suffix(&output.with_extension(""), "_csv"), endianness, platform)
+        }
+    }
+}
+
+// None = ao lado da pasta extraida; a extensao depende da ordem dos bytes, lida do .EFFBLOBTXT
+fn repack_output(
+    root: &Path,
+    extracted: &Path,
+    output: Option<&Path>,
+    endian: EndianArg,
+    platform: Option<Platform>,
+) -> Result<Option<PathBuf>, EffError> {
+    if output.is_none() {
+        return Ok(None);
+    }
+    let endianness = match crate::platform_endianness(platform, endian.endianness())? {
+        Some(endianness) => Some(endianness),
+        None => Manifest::read(extracted)?.endianness,
+    };
+    let big = endianness.is_some_and(|endianness| endianness.is_big());
+    let base = mirror(root, extracted, output)?;
+    Ok(Some(crate::with_suffix(&base, if big { ".EFFBLOBBIG" } else { ".EFFBLOB" })))
+}
+
+// false = terminou, mas com diferencas (verify)
+fn execute(command: Command, jobs: usize) -> Result<bool, EffError> {
+    match command {
+        Command::Extract { file, output, endian, platform, format } if file.is_dir() => {
+            let files = batch::find(&file, &is_effblob)?;
+            return Ok(batch::run(&files, jobs, |path, _| {
+                let base = mirror(&file, path, output.as_deref())?;
+                extract(path, &base, endian.endianness(), platform, format).map(|_| true)
+            }));
+        }
+        Command::Extract { file, output, endian, platform, format } => {
+            let output = match (output, format) {
+                (Some(output), _) => output,
+                (None, Format::Text) => file.with_extension(""),
+                (None, Format::Json) => file.with_extension("json"),
+                (None, Format::Gltf) => file.with_extension("gltf"),
+                (None, Format::Csv) => crate::with_suffix(&file.with_extension(""), "_csv"),
+            };
+            let endianness = endian.endianness();
+            match format {
+                Format::Text => crate::extract(&file, &output, endianness, platform)?,
+                Format::Json => crate::extract_json(&file, &output, endianness, platform)?,
+                Format::Gltf => crate::extract_gltf(&file, &output, endianness, platform)?,
+                Format::Csv => crate::extract_csv(&file, &output, endianness, platform)?,
+            }
+            println!("# Extracted: {}", output.display());
+        }
+        Command::Repack { input, output, endian, platform, no_validate, .. } if has_extension(&input, "json") => {
+            let output = single(|log| {
+                crate::repack_json(&input, output.as_deref(), endian.endianness(), platform, !no_validate, log)
+            })?;
+            println!("# Repacked: {}", output.display());
+        }
+        Command::Repack { input, output, endian, platform, no_validate, copy_raw } => {
+            // o .EFFBLOBTXT, arrastado sobre o .bat, indica a pasta de mesmo nome
+            let folder = if has_extension(&input, "EFFBLOBTXT") { input.with_extension("") } else { input };
+            if folder.is_dir() && !is_extracted(&folder) {
+                let manifests = batch::find(&folder, &|path| has_extension(path, "EFFBLOBTXT"))?;
+                if manifests.is_empty() {
+                    return Err(EffError::Io {
+                        path: Some(folder),
+                        source: std::io::Error::new(
+                            std::io::ErrorKind::NotFound,
+                            "not an extracted folder (no .EFFBLOBTXT next to it, no Tables or Effect folders) \
+                             and no .EFFBLOBTXT found inside it",
+                        ),
+                    });
+                }
+                return Ok(batch::run(&manifests, jobs, |manifest, log| {
+                    let extracted = manifest.with_extension("");
+                    let output = repack_output(&folder, &extracted, output.as_deref(), endian, platform)?;
+                    crate::repack(&extracted, output.as_deref(), endian.endianness(), platform, !no_validate, copy_raw, log)
+                        .map(|_| true)
+                }));
+            }
+            let output = single(|log| {
+                crate::repack(&folder, output.as_deref(), endian.endianness(), platform, !no_validate, copy_raw, log)
+            })?;
+            println!("# Repacked: {}", output.display());
+        }
+        Command::ApplyCsv { file, csv, output, no_validate } => {
+            single(|log| crate::repack_csv(&file, &csv, &output, !no_validate, log))?;
+            println!("# Repacked: {}", output.display());
+        }
+        Command::Info { file, endian, json } => crate::info(&file, endian.endianness(), json)?,
+        Command::Verify { files, endian } => {
+            let mut found = Vec::new();
+            for file in files {
+                if file.is_dir() {
+                    found.extend(batch::find(&file, &is_effblob)?);
+                } else {
+                    found.push(file);
+                }
+            }
+            if found.len() == 1 {
+                return single(|log| crate::verify(&found[0], endian.endianness(), log));
+            }
+            return Ok(batch::run(&found, jobs, |file, log| crate::verify(file, endian.endianness(), log)));
+        }
+        Command::Validate { files, endian, platform } => {
+            let mut found = Vec::new();
+            for file in files {
+                // uma pasta extraida tem o .EFFBLOBTXT ao lado; as outras sao procuradas
+                if file.is_dir() && !is_extracted(&file) {
+                    found.extend(batch::find(&file, &is_effblob)?);
+                } else {
+                    found.push(file);
+                }
+            }
+            if found.len() == 1 {
+                return single(|log| crate::validate(&found[0], endian.endianness(), platform, log));
+            }
+            return Ok(batch::run(&found, jobs, |file, log| crate::validate(file, endian.endianness(), platform, log)));
+        }
+        Command::Diff { old, new, endian, tolerance, relative, json } => {
+            let tolerance = Tolerance {
+                absolute: tolerance,
+                relative,
+            };
+            let mut log = Vec::new();
+            let result = crate::diff(&old, &new, endian.endianness(), tolerance, json, &mut log);
+            // os avisos da leitura nao podem se misturar com o json
+            for line in log {
+                eprintln!("{}", line);
+            }
+            return result;
+        }
+        Command::Merge { base, ours, theirs, output, endian, prefer, report, no_validate } => {
+            let prefer = match prefer {
+                Prefer::Ours => Side::Ours,
+                Prefer::Theirs => Side::Theirs,
+            };
+            let files = [base.as_path(), ours.as_path(), theirs.as_path()];
+            let merged = single(|log| {
+                crate::merge(files, &output, endian.endianness(), prefer, report.as_deref(), !no_validate, log)
+            })?;
+            println!("# Merged: {}", output.display());
+            return Ok(merged);
+        }
+        Command::Convert { file, output, from, endian, platform, no_validate, copy_raw } if file.is_dir() => {
+            let files = batch::find(&file, &is_effblob)?;
+            return Ok(batch::run(&files, jobs, |path, log| {
+                // a pasta de saida do arquivo; o nome vem da ordem dos bytes
+                let base = mirror(&file, path, output.as_deref())?;
+                let folder = base.parent().unwrap_or(Path::new(""));
+                crate::convert(path, Some(folder), from.endianness(), endian.endianness(), platform, !no_validate, copy_raw, log)
+                    .map(|_| true)
+            }));
+        }
+        Command::Convert { file, output, from, endian, platform, no_validate, copy_raw } => {
+            let converted = single(|log| {
+                let (source, target) = (from.endianness(), endian.endianness());
+                crate::convert(&file, output.as_deref(), source, target, platform, !no_validate, copy_raw, log)
+            })?;
+            if let Some(output) = converted {
+                println!("# Converted: {}", output.display());
+            }
+        }
+        Command::Paths { file, output, endian, samples } => {
+            let written = single(|log| crate::export_paths(&file, output.as_deref(), endian.endianness(), samples, log))?;
+            for output in written {
+                println!("# Exported: {}", output.display());
+            }
+        }
+        Command::Schema { output } => crate::write_schema(&output)?,
+    }
+    Ok(true)
+}
+
+/// Exit codes: 0 = ok, 1 = error, verify mismatch or a failed file of a folder, 2 = invalid arguments.
+pub fn run() -> ExitCode {
+    let cli = Cli::parse();
+    match execute(cli.command, cli.jobs.unwrap_or_else(batch::default_jobs)) {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(error) => {
+            eprintln!("# Error: {}", error);
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,504 @@
+use super::{Curve, CurvePoint};
+
+type Vector = (f32, f32, f32);
+
+// nos e pesos de Gauss-Legendre de 5 pontos, no intervalo [-1, 1]
+const GAUSS_LEGENDRE: [(f32, f32); 5] = [
+    (0.0, 0.568_888_9),
+    (-0.538_469_3, 0.478_628_67),
+    (0.538_469_3, 0.478_628_67),
+    (-0.906_179_85, 0.236_926_88),
+    (0.906_179_85, 0.236_926_88),
+];
+// partes de cada segmento na integracao do comprimento, para curvas com handles longos
+const LENGTH_STEPS: usize = 8;
+// iteracoes da bissecao de parameter_in_segment, sobram poucos bits de um f32 depois disso
+const BISECTION_STEPS: usize = 32;
+
+fn add(a: Vector, b: Vector) -> Vector {
+    (a.0 + b.0, a.1 + b.1, a.2 + b.2)
+}
+
+fn sub(a: Vector, b: Vector) -> Vector {
+    (a.0 - b.0, a.1 - b.1, a.2 - b.2)
+}
+
+fn scale(a: Vector, value: f32) -> Vector {
+    (a.0 * value, a.1 * value, a.2 * value)
+}
+
+fn length(a: Vector) -> f32 {
+    (a.0 * a.0 + a.1 * a.1 + a.2 * a.2).sqrt()
+}
+
+fn lerp(a: Vector, b: Vector, t: f32) -> Vector {
+    add(a, scale(sub(b, a), t))
+}
+
+fn axis(a: Vector, index: usize) -> f32 {
+    [a.0, a.1, a.2][index]
+}
+
+// de Casteljau: os pontos intermediarios tambem sao os handles das duas metades
+fn subdivide(control: [Vector; 4], t: f32) -> ([Vector; 3], [Vector; 2], Vector) {
+    let [p0, p1, p2, p3] = control;
+    let (a, b, c) = (lerp(p0, p1, t), lerp(p1, p2, t), lerp(p2, p3, t));
+    let (d, e) = (lerp(a, b, t), lerp(b, c, t));
+    ([a, b, c], [d, e], lerp(d, e, t))
+}
+
+fn position(control: [Vector; 4], t: f32) -> Vector {
+    subdivide(control, t).2
+}
+
+fn derivative(control: [Vector; 4], t: f32) -> Vector {
+    let [p0, p1, p2, p3] = control;
+    let u = 1.0 - t;
+    let sum = add(add(scale(sub(p1, p0), u * u), scale(sub(p2, p1), 2.0 * u * t)), scale(sub(p3, p2), t * t));
+    scale(sum, 3.0)
+}
+
+// comprimento de t0 ate t1 dentro de um segmento
+fn segment_length(control: [Vector; 4], t0: f32, t1: f32) -> f32 {
+    let step = (t1 - t0) / LENGTH_STEPS as f32;
+    let mut total = 0.0;
+    for part in 0..LENGTH_STEPS {
+        let middle = t0 + step * (part as f32 + 0.5);
+        for (node, weight) in GAUSS_LEGENDRE {
+            total += weight * length(derivative(control, middle + node * step / 2.0));
+        }
+    }
+    total * step / 2.0
+}
+
+// t do segmento onde o comprimento desde o inicio é distance; total = o comprimento do segmento
+fn parameter_in_segment(control: [Vector; 4], distance: f32, total: f32) -> f32 {
+    if distance >= total {
+        return 1.0;
+    }
+    let (mut low, mut high) = (0.0, 1.0);
+    for _ in 0..BISECTION_STEPS {
+        let middle = (low + high) / 2.0;
+        if segment_length(control, 0.0, middle) < distance {
+            low = middle;
+        } else {
+            high = middle;
+        }
+    }
+    (low + high) / 2.0
+}
+
+// t em (0, 1) onde a derivada de um eixo é zero (maximos e minimos do eixo)
+fn extremes(control: [Vector; 4], index: usize) -> Vec<f32> {
+    let [p0, p1, p2, p3] = control.map(|point| axis(point, index));
+    // derivada / 3 = a t^2 + b t + c
+    let a = p3 - 3.0 * p2 + 3.0 * p1 - p0;
+    let b = 2.0 * (p2 - 2.0 * p1 + p0);
+    let c = p1 - p0;
+    let roots = if a.abs() < f32::EPSILON {
+        if b.abs() < f32::EPSILON { Vec::new() } else { vec![-c / b] }
+    } else {
+        let discriminant = b * b - 4.0 * a * c;
+        if discriminant < 0.0 {
+            Vec::new()
+        } else {
+            let root = discriminant.sqrt();
+            vec![(-b + root) / (2.0 * a), (-b - root) / (2.0 * a)]
+        }
+    };
+    roots.into_iter().filter(|t| *t > 0.0 && *t < 1.0).collect()
+}
+
+impl CurvePoint {
+    /// Control points of the cubic Bezier segment from this point to `next`: `point`,
+    /// `handle_1`, the `handle_0` of `next` and its `point`. The handles are taken as absolute
+    /// positions, like `point`, not as offsets from it; this is an assumption, Table 9 is not
+    /// fully researched.
+    pub fn segment_to(&self, next: &CurvePoint) -> [(f32, f32, f32); 4] {
+        [self.point, self.handle_1, next.handle_0, next.point]
+    }
+}
+
+/// Bezier evaluation. The parameter `t` goes from 0 (first point) to 1 (last point) over the
+/// whole path, each segment taking an equal part of it: with 4 points, `t` = 1/3 is the
+/// second point. Values outside [0, 1] are clamped.
+impl Curve {
+    /// Number of Bezier segments, one less than the points.
+    pub fn segment_count(&self) -> usize {
+        self.points.len().saturating_sub(1)
+    }
+
+    /// Control points of segment `index`, see [`CurvePoint::segment_to`].
+    pub fn segment(&self, index: usize) -> Option<[(f32, f32, f32); 4]> {
+        Some(self.points.get(index)?.segment_to(self.points.get(index + 1)?))
+    }
+
+    fn segments(&self) -> impl Iterator<Item = [(f32, f32, f32); 4]> + '_ {
+        self.points.windows(2).map(|pair| pair[0].segment_to(&pair[1]))
+    }
+
+    // segmento e t dentro dele
+    fn locate(&self, t: f32) -> Option<(usize, f32)> {
+        let count = self.segment_count();
+        if count == 0 {
+            return None;
+        }
+        let scaled = t.clamp(0.0, 1.0) * count as f32;
+        let index = (scaled as usize).min(count - 1);
+        Some((index, scaled - index as f32))
+    }
+
+    /// Position at `t`. A path with a single point is always at that point, an empty one has none.
+    pub fn position(&self, t: f32) -> Option<(f32, f32, f32)> {
+        match self.locate(t) {
+            Some((index, local)) => Some(position(self.segment(index)?, local)),
+            None => self.points.first().map(|first| first.point),
+        }
+    }
+
+    /// Derivative of the segment at `t`, not normalized: its length is how fast the position
+    /// moves along the segment. Zero where the handles are on the point. `None` with less than
+    /// two points.
+    pub fn tangent(&self, t: f32) -> Option<(f32, f32, f32)> {
+        let (index, local) = self.locate(t)?;
+        Some(derivative(self.segment(index)?, local))
+    }
+
+    /// Length along the curve, in game units.
+    pub fn arc_length(&self) -> f32 {
+        self.segments().map(|control| segment_length(control, 0.0, 1.0)).sum()
+    }
+
+    /// The `t` at `distance` along the curve, clamped to the ends.
+    pub fn parameter_at_distance(&self, distance: f32) -> Option<f32> {
+        let count = self.segment_count();
+        if count == 0 {
+            return None;
+        }
+        let mut left = distance.max(0.0);
+        for (index, control) in self.segments().enumerate() {
+            let total = segment_length(control, 0.0, 1.0);
+            if left <= total && total > 0.0 {
+                return Some((index as f32 + parameter_in_segment(control, left, total)) / count as f32);
+            }
+            left -= total;
+        }
+        Some(1.0)
+    }
+
+    /// Positions evaluated at `samples` equal steps of `t` on each segment, after the first
+    /// point; the last one of each segment is the next point. An empty path gives an empty list.
+    pub fn sample(&self, samples: usize) -> Vec<(f32, f32, f32)> {
+        let samples = samples.max(1);
+        let mut result: Vec<(f32, f32, f32)> = self.points.first().map(|first| first.point).into_iter().collect();
+        for control in self.segments() {
+            result.extend((1..=samples).map(|step| position(control, step as f32 / samples as f32)));
+        }
+        result
+    }
+
+    /// Positions every `spacing` units along the curve, starting at the first point; the last
+    /// point is included only when the length is a multiple of `spacing`. Empty when `spacing`
+    /// is not positive.
+    pub fn sample_by_distance(&self, spacing: f32) -> Vec<(f32, f32, f32)> {
+        let Some(first) = self.points.first() else { return Vec::new() };
+        if spacing.is_nan() || spacing <= 0.0 {
+            return Vec::new();
+        }
+        let mut result = vec![first.point];
+        // a distancia do proximo ponto é step * spacing; somar spacing acumularia o erro
+        let mut step = 1;
+        let mut start = 0.0;
+        for control in self.segments() {
+            let total = segment_length(control, 0.0, 1.0);
+            // um ponto infinito ou NaN deixaria o laco sem fim; o resto do path nao tem posicao valida
+            if !total.is_finite() {
+                break;
+            }
+            while step as f32 * spacing <= start + total {
+                let local = parameter_in_segment(control, step as f32 * spacing - start, total);
+                result.push(position(control, local));
+                step += 1;
+            }
+            start += total;
+        }
+        result
+    }
+
+    /// Smallest box `[minimum, maximum]` that holds the whole curve, not only the control points.
+    pub fn bounding_box(&self) -> Option<[(f32, f32, f32); 2]> {
+        let first = self.points.first()?.point;
+        let (mut min, mut max) = ([first.0, first.1, first.2], [first.0, first.1, first.2]);
+        for control in self.segments() {
+            let mut found = vec![control[3]];
+            for index in 0..3 {
+                found.extend(extremes(control, index).into_iter().map(|t| position(control, t)));
+            }
+            for point in found {
+                for index in 0..3 {
+                    min[index] = min[index].min(axis(point, index));
+                    max[index] = max[index].max(axis(point, index));
+                }
+            }
+        }
+        Some([(min[0], min[1], min[2]), (max[0], max[1], max[2])])
+    }
+
+    /// Adds a point at `t` without changing the shape: the handles around it are shortened as
+    /// in de Casteljau's algorithm. `unknown` is interpolated from the two neighbours. Returns
+    /// the index of the point, an existing one when `t` falls on it; `None` with less than
+    /// two points. The `t` of the rest of the curve changes, as there is one more segment.
+    pub fn insert_point(&mut self, t: f32) -> Option<usize> {
+        let (index, local) = self.locate(t)?;
+        if local <= 0.0 {
+            return Some(index);
+        }
+        if local >= 1.0 {
+            return Some(index + 1);
+        }
+        let ([a, _, c], [d, e], point) = subdivide(self.segment(index)?, local);
+        let unknown = self.points[index].unknown + (self.points[index + 1].unknown - self.points[index].unknown) * local;
+        self.points[index].handle_1 = a;
+        self.points[index + 1].handle_0 = c;
+        self.points.insert(
+            index + 1,
+            CurvePoint {
+                point,
+                handle_0: d,
+                handle_1: e,
+                unknown,
+            },
+        );
+        Some(index + 1)
+    }
+
+    /// The curve cut at `t` in two, both with the point at `t`; together they have the same shape.
+    pub fn split(&self, t: f32) -> Option<(Curve, Curve)> {
+        let mut curve = self.clone();
+        let index = curve.insert_point(t)?;
+        let second = curve.points[index..].to_vec();
+        curve.points.truncate(index + 1);
+        Some((curve, Curve { points: second }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eff::sample;
+
+    fn knot(point: Vector, handle_0: Vector, handle_1: Vector) -> CurvePoint {
+        CurvePoint {
+            point,
+            handle_0,
+            handle_1,
+            unknown: 0.0,
+        }
+    }
+
+    fn assert_close(found: Vector, expected: Vector, tolerance: f32) {
+        assert!(length(sub(found, expected)) <= tolerance, "{:?} is not {:?}", found, expected);
+    }
+
+    // reta de 0 a 100 em x com os handles a 1/3 e 2/3, velocidade constante
+    fn uniform_line() -> Curve {
+        Curve {
+            points: vec![
+                knot((0.0, 0.0, 0.0), (0.0, 0.0, 0.0), (100.0 / 3.0, 0.0, 0.0)),
+                knot((100.0, 0.0, 0.0), (200.0 / 3.0, 0.0, 0.0), (100.0, 0.0, 0.0)),
+            ],
+        }
+    }
+
+    // arco simetrico: sobe ate y = 75 no meio
+    fn arch() -> Curve {
+        Curve {
+            points: vec![
+                knot((0.0, 0.0, 0.0), (0.0, 0.0, 0.0), (0.0, 100.0, 0.0)),
+                knot((100.0, 0.0, 0.0), (100.0, 100.0, 0.0), (100.0, 0.0, 0.0)),
+            ],
+        }
+    }
+
+    #[test]
+    fn straight_line() {
+        // handles em cima dos pontos, como nos arquivos do jogo
+        let curve = sample::eff().paths.remove(0);
+        assert_eq!(curve.segment_count(), 1);
+        assert_close(curve.position(0.0).unwrap(), (0.0, 0.0, 0.0), 0.0);
+        assert_close(curve.position(0.5).unwrap(), (50.0, 0.0, 0.0), 1e-4);
+        assert_close(curve.position(2.0).unwrap(), (100.0, 0.0, 0.0), 0.0);
+        assert!((curve.arc_length() - 100.0).abs() < 1e-3);
+        assert_close(curve.tangent(0.0).unwrap(), (0.0, 0.0, 0.0), 0.0);
+        assert_close(uniform_line().tangent(0.3).unwrap(), (100.0, 0.0, 0.0), 1e-3);
+    }
+
+    #[test]
+    fn short_paths() {
+        let mut curve = Curve { points: Vec::new() };
+        assert_eq!(curve.position(0.5), None);
+        assert_eq!(curve.bounding_box(), None);
+        assert!(curve.sample(4).is_empty());
+        assert!(curve.sample_by_distance(10.0).is_empty());
+
+        curve.points.push(sample::point(1.0, 2.0, 3.0));
+        assert_eq!(curve.sample_by_distance(10.0), [(1.0, 2.0, 3.0)]);
+        assert_eq!(curve.position(0.5), Some((1.0, 2.0, 3.0)));
+        assert_eq!(curve.tangent(0.5), None);
+        assert_eq!(curve.parameter_at_distance(1.0), None);
+        assert_eq!(curve.insert_point(0.5), None);
+        assert_eq!(curve.arc_length(), 0.0);
+        assert_eq!(curve.sample(4), [(1.0, 2.0, 3.0)]);
+    }
+
+    #[test]
+    fn symmetric_cubic() {
+        let curve = arch();
+        assert_close(curve.position(0.5).unwrap(), (50.0, 75.0, 0.0), 1e-4);
+        // simetrico em x = 50
+        let (left, right) = (curve.position(0.2).unwrap(), curve.position(0.8).unwrap());
+        assert_close((100.0 - left.0, left.1, left.2), right, 1e-3);
+        // no topo a tangente é horizontal
+        assert!(curve.tangent(0.5).unwrap().1.abs() < 1e-3);
+    }
+
+    #[test]
+    fn de_casteljau() {
+        let control = arch().segment(0).unwrap();
+        let ([a, b, c], [d, e], point) = subdivide(control, 0.25);
+        assert_close(point, position(control, 0.25), 1e-4);
+        assert_close(b, lerp(control[1], control[2], 0.25), 0.0);
+        // as duas metades sao a mesma curva
+        let (first, second) = ([control[0], a, d, point], [point, e, c, control[3]]);
+        for t in [0.1, 0.5, 0.9] {
+            assert_close(position(first, t), position(control, t * 0.25), 1e-3);
+            assert_close(position(second, t), position(control, 0.25 + t * 0.75), 1e-3);
+        }
+    }
+
+    #[test]
+    fn quarter_circle_length() {
+        // aproximacao cubica de um quarto de circulo de raio 100
+        let k = 100.0 * 0.552_284_8;
+        let curve = Curve {
+            points: vec![
+                knot((100.0, 0.0, 0.0), (100.0, 0.0, 0.0), (100.0, k, 0.0)),
+                knot((0.0, 100.0, 0.0), (k, 100.0, 0.0), (0.0, 100.0, 0.0)),
+            ],
+        };
+        let expected = std::f32::consts::FRAC_PI_2 * 100.0;
+        assert!((curve.arc_length() - expected).abs() < 0.05, "{}", curve.arc_length());
+        // handles longos: o comprimento de uma parte bate com a soma das partes
+        let halves = segment_length(curve.segment(0).unwrap(), 0.0, 0.5)
+            + segment_length(curve.segment(0).unwrap(), 0.5, 1.0);
+        assert!((halves - curve.arc_length()).abs() < 1e-3);
+    }
+
+    #[test]
+    fn parameter_at_distance() {
+        let curve = uniform_line();
+        assert!((curve.parameter_at_distance(25.0).unwrap() - 0.25).abs() < 1e-4);
+        assert!(curve.parameter_at_distance(-5.0).unwrap() < 1e-6);
+        assert_eq!(curve.parameter_at_distance(500.0), Some(1.0));
+
+        // na curva, a bissecao encontra o t da distancia pedida
+        let arch = arch();
+        let t = arch.parameter_at_distance(40.0).unwrap();
+        let (first, _) = arch.split(t).unwrap();
+        assert!((first.arc_length() - 40.0).abs() < 1e-2);
+
+        // dois segmentos: o t do segundo comeca em 0.5
+        let mut two = uniform_line();
+        two.points.push(knot((200.0, 0.0, 0.0), (400.0 / 3.0, 0.0, 0.0), (200.0, 0.0, 0.0)));
+        two.points[1].handle_1 = (400.0 / 3.0, 0.0, 0.0);
+        two.points[2].handle_0 = (500.0 / 3.0, 0.0, 0.0);
+        assert!((two.parameter_at_distance(150.0).unwrap() - 0.75).abs() < 1e-4);
+    }
+
+    #[test]
+    fn split() {
+        let curve = arch();
+        let (first, second) = curve.split(0.3).unwrap();
+        let middle = curve.position(0.3).unwrap();
+        assert_eq!(first.points.last().unwrap().point, middle);
+        assert_eq!(second.points[0].point, middle);
+        assert_eq!(first.points[0].point, curve.points[0].point);
+        assert_eq!(second.points.last().unwrap().point, curve.points[1].point);
+        assert!((first.arc_length() + second.arc_length() - curve.arc_length()).abs() < 1e-2);
+    }
+
+    #[test]
+    fn bounding_box() {
+        // curva em S: os handles vao a +-100 em y, mas a curva so chega a 100 / (2 * sqrt(3))
+        let curve = Curve {
+            points: vec![
+                knot((0.0, 0.0, 0.0), (0.0, 0.0, 0.0), (100.0 / 3.0, 100.0, 0.0)),
+                knot((100.0, 0.0, 0.0), (200.0 / 3.0, -100.0, 0.0), (100.0, 0.0, 0.0)),
+            ],
+        };
+        let [min, max] = curve.bounding_box().unwrap();
+        let peak = 100.0 / (2.0 * 3f32.sqrt());
+        assert_close(min, (0.0, -peak, 0.0), 1e-3);
+        assert_close(max, (100.0, peak, 0.0), 1e-3);
+
+        let [min, max] = arch().bounding_box().unwrap();
+        assert_close(min, (0.0, 0.0, 0.0), 1e-4);
+        assert_close(max, (100.0, 75.0, 0.0), 1e-3);
+    }
+
+    #[test]
+    fn insert_point() {
+        let original = arch();
+        let mut curve = original.clone();
+        curve.points[1].unknown = 10.0;
+        assert_eq!(curve.insert_point(0.3), Some(1));
+        assert_eq!(curve.points.len(), 3);
+        assert!((curve.points[1].unknown - 3.0).abs() < 1e-4);
+        assert_close(curve.points[1].point, original.position(0.3).unwrap(), 1e-4);
+
+        // a forma nao muda: o trecho [0, 0.3] vira o primeiro segmento, [0.3, 1] o segundo
+        for t in [0.1, 0.2] {
+            assert_close(curve.position(t / 0.3 / 2.0).unwrap(), original.position(t).unwrap(), 1e-3);
+        }
+        for t in [0.5, 0.9] {
+            let local = (t - 0.3) / 0.7;
+            assert_close(curve.position(0.5 + local / 2.0).unwrap(), original.position(t).unwrap(), 1e-3);
+        }
+        assert!((curve.arc_length() - original.arc_length()).abs() < 1e-2);
+
+        // em cima de um ponto, nada é inserido
+        assert_eq!(curve.insert_point(0.0), Some(0));
+        assert_eq!(curve.insert_point(0.5), Some(1));
+        assert_eq!(curve.insert_point(1.0), Some(2));
+        assert_eq!(curve.points.len(), 3);
+    }
+
+    #[test]
+    fn samples() {
+        let curve = uniform_line();
+        let points = curve.sample(4);
+        assert_eq!(points.len(), 5);
+        assert_eq!(points[0], (0.0, 0.0, 0.0));
+        assert_close(points[1], (25.0, 0.0, 0.0), 1e-3);
+        assert_close(points[4], (100.0, 0.0, 0.0), 1e-4);
+        assert_eq!(curve.sample(0).len(), 2);
+
+        let points = curve.sample_by_distance(25.0);
+        assert_eq!(points.len(), 5);
+        for (index, point) in points.iter().enumerate() {
+            assert_close(*point, (index as f32 * 25.0, 0.0, 0.0), 1e-2);
+        }
+        // o ultimo ponto so entra quando o comprimento é multiplo do espacamento
+        assert_eq!(curve.sample_by_distance(30.0).len(), 4);
+        assert!(curve.sample_by_distance(0.0).is_empty());
+        assert!(curve.sample_by_distance(f32::NAN).is_empty());
+        assert_eq!(curve.sample_by_distance(f32::INFINITY).len(), 1);
+
+        // segmentos com comprimento infinito ou NaN encerram a amostragem
+        for bad in [f32::INFINITY, f32::NAN, f32::MAX] {
+            let mut curve = uniform_line();
+            curve.points.push(knot((bad, 0.0, 0.0), (100.0, 0.0, 0.0), (bad, 0.0, 0.0)));
+            assert_eq!(curve.sample_by_distance(25.0).len(), 5);
+        }
+    }
+}
@@ -0,0 +1,323 @@
+use super::reader::{Block, BlockReader, Field};
+use super::text::{effect_fields, read_effect_from_file};
+use super::{Eff, EffError, Effect, EffectGroup, Result};
+use ::csv::{ReaderBuilder, StringRecord, WriterBuilder};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+const GROUP_COLUMN: &str = "Group";
+const EFFECT_COLUMN: &str = "Effect";
+
+/// Name of the CSV of an effect table, "Effect 0.csv" or "Effect 1.csv".
+fn csv_name(table: usize) -> String {
+    format!("Effect {}.csv", table)
+}
+
+/// One row per effect: "Group", "Effect" and the fields of the "Effect Group N Data.txt2"
+/// files, with the same names and value format.
+pub fn to_csv(groups: &[EffectGroup]) -> Result<String> {
+    let error = |source| EffError::Csv { path: None, source };
+    let mut writer = WriterBuilder::new().from_writer(Vec::new());
+
+    let mut header = vec![GROUP_COLUMN, EFFECT_COLUMN];
+    header.extend(effect_fields(&Effect::default()).iter().map(|(key, _)| *key));
+    writer.write_record(&header).map_err(error)?;
+
+    for (group_index, group) in groups.iter().enumerate() {
+        for (effect_index, effect) in group.effects.iter().enumerate() {
+            let mut row = vec![group_index.to_string(), effect_index.to_string()];
+            row.extend(effect_fields(effect).into_iter().map(|(_, value)| value));
+            writer.write_record(&row).map_err(error)?;
+        }
+    }
+
+    let bytes = writer.into_inner().map_err(|error| EffError::from(error.into_error()))?;
+    Ok(String::from_utf8(bytes).expect("the fields are always UTF-8"))
+}
+
+// planilhas em pt-BR salvam com ";" como separador
+fn delimiter(text: &str) -> u8 {
+    let header = text.lines().next().unwrap_or_default();
+    let count = |separator: char| header.matches(separator).count();
+    if count(';') > count(',') && count(';') >= count('\t') {
+        b';'
+    } else if count('\t') > count(',') {
+        b'\t'
+    } else {
+        b','
+    }
+}
+
+// "1,5" é o mesmo que "1.5"; valores hex nunca tem virgula
+fn decimal(value: &str) -> String {
+    if value.contains(',') && !value.contains('.') {
+        value.replace(',', ".")
+    } else {
+        value.to_owned()
+    }
+}
+
+// indice das colunas Group e Effect
+fn index_column(path: &Path, header: &StringRecord, name: &str) -> Result<usize> {
+    header.iter().position(|column| column.trim() == name).ok_or_else(|| EffError::MissingField {
+        path: path.to_path_buf(),
+        line: 1,
+        field: name.to_owned(),
+    })
+}
+
+fn parse_index(path: &Path, line: usize, field: &str, value: &str) -> Result<usize> {
+    value.trim().parse().map_err(|_| EffError::Parse {
+        path: path.to_path_buf(),
+        line,
+        field: field.to_owned(),
+        value: value.to_owned(),
+    })
+}
+
+/// Applies the rows of a CSV written by [`to_csv`] over `groups`. Each row replaces the
+/// effect at its Group/Effect; columns left out of the file and empty cells keep the current
+/// value, so a CSV with only some columns or some rows also works. A row right after the
+/// last effect of a group adds a new effect. Decimals can use "." or ",", and the file can
+/// be separated by ",", ";" or tabs. Every row is checked first, so on an error `groups` is
+/// left unchanged.
+pub fn apply_csv(groups: &mut [EffectGroup], path: &Path, text: &str, warnings: &mut Vec<String>) -> Result<()> {
+    let error = |source| EffError::Csv {
+        path: Some(path.to_path_buf()),
+        source,
+    };
+    let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+    let mut reader = ReaderBuilder::new()
+        .delimiter(delimiter(text))
+        .from_reader(text.as_bytes());
+
+    let header = reader.headers().map_err(error)?.clone();
+    let group_column = index_column(path, &header, GROUP_COLUMN)?;
+    let effect_column = index_column(path, &header, EFFECT_COLUMN)?;
+    let known: Vec<&str> = effect_fields(&Effect::default()).iter().map(|(key, _)| *key).collect();
+    for column in header.iter().map(str::trim) {
+        if column != GROUP_COLUMN && column != EFFECT_COLUMN && !known.contains(&column) {
+            warnings.push(format!("{}: unknown column \"{}\", ignored", path.display(), column));
+        }
+    }
+
+    // linhas ordenadas por grupo/efeito, para que efeitos novos entrem na ordem
+    let mut rows = BTreeMap::<(usize, usize), (usize, StringRecord)>::new();
+    for record in reader.records() {
+        let record = record.map_err(error)?;
+        let line = record.position().map_or(0, |position| position.line() as usize);
+        if record.iter().all(|value| value.trim().is_empty()) {
+            continue;
+        }
+        let group = parse_index(path, line, GROUP_COLUMN, record.get(group_column).unwrap_or_default())?;
+        let effect = parse_index(path, line, EFFECT_COLUMN, record.get(effect_column).unwrap_or_default())?;
+        if rows.insert((group, effect), (line, record)).is_some() {
+            return Err(EffError::Duplicate {
+                path: path.to_path_buf(),
+                line,
+                name: format!("Group {} Effect {}", group, effect),
+            });
+        }
+    }
+
+    // todas as linhas sao lidas e conferidas antes de mudar os grupos, assim um erro no meio
+    // do arquivo nao deixa metade das linhas aplicadas
+    let mut lengths: Vec<usize> = groups.iter().map(|group| group.effects.len()).collect();
+    let mut changes = Vec::<(usize, usize, Effect)>::with_capacity(rows.len());
+    for ((group, effect), (line, record)) in rows {
+        let group_error = || EffError::Parse {
+            path: path.to_path_buf(),
+            line,
+            field: GROUP_COLUMN.to_owned(),
+            value: group.to_string(),
+        };
+        let length = lengths.get_mut(group).ok_or_else(group_error)?;
+        if effect > *length {
+            return Err(EffError::Parse {
+                path: path.to_path_buf(),
+                line,
+                field: EFFECT_COLUMN.to_owned(),
+                value: effect.to_string(),
+            });
+        }
+        if effect == *length {
+            *length += 1;
+        }
+
+        // valores atuais, substituidos pelas celulas preenchidas
+        let current = groups[group].effects.get(effect).cloned().unwrap_or_default();
+        let mut fields: Vec<Field> = effect_fields(&current)
+            .into_iter()
+            .map(|(key, value)| Field {
+                key: key.to_owned(),
+                value,
+                line,
+            })
+            .collect();
+        for (column, value) in header.iter().zip(record.iter()) {
+            let value = value.trim();
+            if value.is_empty() {
+                continue;
+            }
+            if let Some(field) = fields.iter_mut().find(|field| field.key == column.trim()) {
+                field.value = decimal(value);
+            }
+        }
+
+        let block = Block {
+            title: Some((format!("Group {} Effect", group), effect)),
+            line,
+            fields,
+            children: Vec::new(),
+        };
+        let mut reader = BlockReader::new(path, &block);
+        changes.push((group, effect, read_effect_from_file(&mut reader)?));
+        reader.finish(warnings);
+    }
+
+    // as linhas estao em ordem, entao um efeito novo sempre entra no fim do grupo
+    for (group, effect, new) in changes {
+        let effects = &mut groups[group].effects;
+        match effects.get_mut(effect) {
+            Some(old) => *old = new,
+            None => effects.push(new),
+        }
+    }
+
+    Ok(())
+}
+
+/// Writes "Effect 0.csv" and "Effect 1.csv" into the folder `path`.
+pub fn write_to_csv(eff: &Eff, path: &Path) -> Result<()> {
+    std::fs::create_dir_all(path).map_err(|error| EffError::io(path, error))?;
+    for (table, groups) in [(0, &eff.effects_0), (1, &eff.effects_1)] {
+        let file = path.join(csv_name(table));
+        std::fs::write(&file, to_csv(groups)?).map_err(|error| EffError::io(&file, error))?;
+    }
+    Ok(())
+}
+
+/// Applies the CSVs found in the folder `path` over `eff`, a missing CSV leaves its table
+/// unchanged. Returns the warnings. On an error `eff` is left unchanged.
+pub fn read_from_csv(eff: &mut Eff, path: &Path) -> Result<Vec<String>> {
+    let mut warnings = Vec::new();
+    // as duas tabelas sao aplicadas em copias: um erro no "Effect 1.csv" nao deixa o Effect 0 mudado
+    let mut tables = [eff.effects_0.clone(), eff.effects_1.clone()];
+    for (table, groups) in tables.iter_mut().enumerate() {
+        let file = path.join(csv_name(table));
+        if !file.is_file() {
+            continue;
+        }
+        let text = std::fs::read_to_string(&file).map_err(|error| EffError::io(&file, error))?;
+        apply_csv(groups, &file, &text, &mut warnings)?;
+    }
+    [eff.effects_0, eff.effects_1] = tables;
+    Ok(warnings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eff::sample;
+
+    fn apply(groups: &mut [EffectGroup], text: &str) -> Result<Vec<String>> {
+        let mut warnings = Vec::new();
+        apply_csv(groups, Path::new("Effect 0.csv"), text, &mut warnings)?;
+        Ok(warnings)
+    }
+
+    #[test]
+    fn delimiter_detection() {
+        assert_eq!(delimiter("Group,Effect,Position X\n0,0,1.5"), b',');
+        assert_eq!(delimiter("Group;Effect;Position X\n0;0;1,5"), b';');
+        assert_eq!(delimiter("Group\tEffect\tPosition X\n"), b'\t');
+        // so o cabecalho conta, as virgulas decimais das linhas nao
+        assert_eq!(delimiter("Group;Effect\n0,5,0,5,0,5"), b';');
+        assert_eq!(delimiter(""), b',');
+    }
+
+    #[test]
+    fn decimal_comma() {
+        assert_eq!(decimal("1,5"), "1.5");
+        assert_eq!(decimal("-0,25"), "-0.25");
+        assert_eq!(decimal("1.5"), "1.5");
+        assert_eq!(decimal("0x1F"), "0x1F");
+    }
+
+    #[test]
+    fn round_trip() {
+        let eff = sample::eff();
+        let mut groups = vec![EffectGroup::default(); eff.effects_0.len()];
+        for (group, original) in groups.iter_mut().zip(&eff.effects_0) {
+            group.effects = vec![Effect::default(); original.effects.len()];
+        }
+        let warnings = apply(&mut groups, &to_csv(&eff.effects_0).unwrap()).unwrap();
+        assert!(warnings.is_empty(), "{:?}", warnings);
+        for (group, original) in groups.iter().zip(&eff.effects_0) {
+            assert_eq!(group.effects, original.effects);
+        }
+    }
+
+    #[test]
+    fn semicolons_and_partial_columns() {
+        let mut groups = sample::eff().effects_0;
+        let before = groups[0].effects[0].clone();
+        let warnings = apply(&mut groups, "\u{feff}Group;Effect;Position X;Width;Color\n0;0;1,5;;red\n").unwrap();
+        assert_eq!(warnings, ["Effect 0.csv: unknown column \"Color\", ignored"]);
+        let effect = &groups[0].effects[0];
+        assert_eq!(effect.position.0, 1.5);
+        // celula vazia e colunas ausentes mantem o valor
+        assert_eq!(effect.width, before.width);
+        assert_eq!(effect.position.1, before.position.1);
+        assert_eq!(groups[0].effects[1], sample::eff().effects_0[0].effects[1]);
+    }
+
+    #[test]
+    fn new_and_invalid_rows() {
+        let mut groups = sample::eff().effects_0;
+        apply(&mut groups, "Group,Effect,ESP ID\n0,2,0x9\n").unwrap();
+        assert_eq!(groups[0].effects.len(), 3);
+        assert_eq!(groups[0].effects[2].esp_id, 9);
+
+        assert!(matches!(apply(&mut groups, "Group,Effect\n0,5\n"), Err(EffError::Parse { line: 2, .. })));
+        assert!(matches!(apply(&mut groups, "Group,Effect\n1,0\n"), Err(EffError::Parse { .. })));
+        assert!(matches!(apply(&mut groups, "Group,Effect\n0,0\n0,0\n"), Err(EffError::Duplicate { line: 3, .. })));
+        assert!(matches!(apply(&mut groups, "Effect,Width\n0,1\n"), Err(EffError::MissingField { .. })));
+    }
+
+    // um erro em qualquer linha nao deixa as linhas anteriores aplicadas
+    #[test]
+    fn failed_import_changes_nothing() {
+        let original = sample::eff().effects_0;
+        for text in [
+            "Group,Effect,Width\n0,0,1\n0,1,abc\n",
+            "Group,Effect,Width\n0,0,1\n0,2,2\n0,4,3\n",
+            "Group,Effect,Width\n0,0,1\n0,2,2\n3,0,3\n",
+            "Group,Effect,Width\n0,0,1\n0,1,2\nx,0,3\n",
+        ] {
+            let mut groups = original.clone();
+            assert!(apply(&mut groups, text).is_err(), "{}", text);
+            assert_eq!(groups, original, "{}", text);
+        }
+
+        // linhas novas seguidas no mesmo grupo continuam validas
+        let mut groups = original.clone();
+        apply(&mut groups, "Group,Effect,Width\n0,3,4\n0,2,3\n").unwrap();
+        assert_eq!(groups[0].effects.len(), 4);
+        assert_eq!((groups[0].effects[2].width, groups[0].effects[3].width), (3.0, 4.0));
+    }
+
+    #[test]
+    fn failed_folder_import_changes_nothing() {
+        let dir = std::env::temp_dir().join(format!("re4_effblob_csv_{}", std::process::id()));
+        let mut eff = sample::eff();
+        write_to_csv(&eff, &dir).unwrap();
+        std::fs::write(dir.join(csv_name(0)), "Group,Effect,Width\n0,0,1\n").unwrap();
+        std::fs::write(dir.join(csv_name(1)), "Group,Effect,Width\n0,0,abc\n").unwrap();
+        let result = read_from_csv(&mut eff, &dir);
+        let _ = std::fs::remove_dir_all(&dir);
+
+        assert!(matches!(result, Err(EffError::Parse { .. })));
+        assert_eq!(eff, sample::eff());
+    }
+}
@@ -0,0 +1,332 @@
+use super::text::{
+    CURVE_POINT_LABELS, EAR_LINK_LABELS, EFFECT_GROUP_LABELS, EFFECT_LABELS, TABLE_ENTRY_LABELS,
+    TEXTURE_METADATA_LABELS,
+};
+use super::{Curve, Eff, EffectGroup, SECTION_NAMES};
+use serde::Serialize;
+use serde_json::Value;
+use std::fmt;
+
+/// How two float values may differ and still be equal: `absolute`, or `relative`
+/// to the larger of the two. Integers are always compared exactly.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Tolerance {
+    pub absolute: f64,
+    pub relative: f64,
+}
+
+impl Tolerance {
+    fn equal(&self, old: f64, new: f64) -> bool {
+        let difference = (old - new).abs();
+        old == new || difference <= self.absolute || difference <= self.relative * old.abs().max(new.abs())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    Changed,
+}
+
+/// A difference found by [`diff`]. `field` is the name of the field in the txt2 files for
+/// `Changed` ("Position Y"), values are in the form of the JSON document.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Change {
+    pub kind: ChangeKind,
+    pub location: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub field: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub old: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub new: Option<Value>,
+}
+
+// valores grandes (um grupo inteiro, bytes da Table 5) sao cortados no texto; o json tem tudo
+fn short(value: &Value) -> String {
+    const MAX: usize = 80;
+    let text = value.to_string();
+    match text.char_indices().nth(MAX) {
+        Some((end, _)) => format!("{}...", &text[..end]),
+        None => text,
+    }
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.kind, &self.field, &self.old, &self.new) {
+            (ChangeKind::Changed, Some(field), Some(old), Some(new)) => {
+                write!(f, "~ {}: {}: {} -> {}", self.location, field, short(old), short(new))
+            }
+            (ChangeKind::Added, _, _, Some(new)) => write!(f, "+ {}: {}", self.location, short(new)),
+            (ChangeKind::Removed, _, Some(old), _) => write!(f, "- {}: {}", self.location, short(old)),
+            _ => write!(f, "~ {}", self.location),
+        }
+    }
+}
+
+// passa pelo texto para que um f32 apareca como no documento json (50.0005, e nao 50.0004997253418)
+pub(super) fn to_value<T: Serialize>(value: &T) -> Value {
+    serde_json::to_string(value)
+        .and_then(|text| serde_json::from_str(&text))
+        .expect("the Eff model always serializes")
+}
+
+struct Diff {
+    tolerance: Tolerance,
+    changes: Vec<Change>,
+}
+
+impl Diff {
+    fn equal(&self, old: &Value, new: &Value) -> bool {
+        match (old, new) {
+            (Value::Number(a), Value::Number(b)) if a.is_f64() || b.is_f64() => match (a.as_f64(), b.as_f64()) {
+                (Some(a), Some(b)) => self.tolerance.equal(a, b),
+                _ => false,
+            },
+            (Value::Array(a), Value::Array(b)) => a.len() == b.len() && a.iter().zip(b).all(|(a, b)| self.equal(a, b)),
+            _ => old == new,
+        }
+    }
+
+    fn push(&mut self, kind: ChangeKind, location: &str, field: Option<&str>, old: Option<Value>, new: Option<Value>) {
+        self.changes.push(Change {
+            kind,
+            location: location.to_owned(),
+            field: field.map(str::to_owned),
+            old,
+            new,
+        });
+    }
+
+    // campos de uma struct, um por um, com o nome dos txt2; tuplas sao comparadas valor a valor.
+    // Campos fora da tabela (as listas de efeitos) sao comparados a parte
+    fn fields<T: Serialize>(&mut self, location: &str, old: &T, new: &T, labels: &[(&str, &[&str])]) {
+        let (old, new) = (to_value(old), to_value(new));
+        for (name, labels) in labels {
+            let (Some(old), Some(new)) = (old.get(name), new.get(name)) else {
+                continue;
+            };
+            match (old, new) {
+                (Value::Array(old), Value::Array(new)) if labels.len() > 1 => {
+                    for ((label, old), new) in labels.iter().zip(old).zip(new) {
+                        self.value(location, label, old, new);
+                    }
+                }
+                _ => self.value(location, labels[0], old, new),
+            }
+        }
+    }
+
+    fn value(&mut self, location: &str, field: &str, old: &Value, new: &Value) {
+        if !self.equal(old, new) {
+            self.push(ChangeKind::Changed, location, Some(field), Some(old.clone()), Some(new.clone()));
+        }
+    }
+
+    // listas sao comparadas pelo indice, que é como o jogo faz referencia a elas
+    fn list<T: Serialize>(
+        &mut self,
+        location: &str,
+        item: &str,
+        old: &[T],
+        new: &[T],
+        mut each: impl FnMut(&mut Diff, &str, &T, &T),
+    ) {
+        let name = |index: usize| format!("{}, {} {}", location, item, index);
+        for (index, (old, new)) in old.iter().zip(new).enumerate() {
+            each(self, &name(index), old, new);
+        }
+        for (index, old) in old.iter().enumerate().skip(new.len()) {
+            self.push(ChangeKind::Removed, &name(index), None, Some(to_value(old)), None);
+        }
+        for (index, new) in new.iter().enumerate().skip(old.len()) {
+            self.push(ChangeKind::Added, &name(index), None, None, Some(to_value(new)));
+        }
+    }
+
+    fn table<T: Serialize>(&mut self, table: usize, old: &[T], new: &[T], labels: &[(&str, &[&str])]) {
+        self.list(SECTION_NAMES[table], "Entry", old, new, |diff, location, old, new| {
+            diff.fields(location, old, new, labels)
+        });
+    }
+
+    fn raw(&mut self, table: usize, old: &[u8], new: &[u8]) {
+        if old != new {
+            self.push(ChangeKind::Changed, SECTION_NAMES[table], Some("bytes"), Some(to_value(&old)), Some(to_value(&new)));
+        }
+    }
+
+    fn groups(&mut self, table: usize, old: &[EffectGroup], new: &[EffectGroup]) {
+        self.list(SECTION_NAMES[table], "Group", old, new, |diff, location, old, new| {
+            diff.fields(location, old, new, EFFECT_GROUP_LABELS);
+            diff.list(location, "Effect", &old.effects, &new.effects, |diff, location, old, new| {
+                diff.fields(location, old, new, EFFECT_LABELS)
+            });
+        });
+    }
+
+    fn paths(&mut self, old: &[Curve], new: &[Curve]) {
+        self.list(SECTION_NAMES[9], "Path", old, new, |diff, location, old, new| {
+            diff.list(location, "Point", &old.points, &new.points, |diff, location, old, new| {
+                diff.fields(location, old, new, CURVE_POINT_LABELS)
+            });
+        });
+    }
+}
+
+/// Differences from `old` to `new`, table by table, in file order. Groups, effects, table
+/// entries and path points are matched by index; a longer list shows up as added items.
+pub fn diff(old: &Eff, new: &Eff, tolerance: Tolerance) -> Vec<Change> {
+    let mut diff = Diff {
+        tolerance,
+        changes: Vec::new(),
+    };
+    diff.table(0, &old.texture_ids, &new.texture_ids, TABLE_ENTRY_LABELS);
+    diff.table(1, &old.core_ids, &new.core_ids, TABLE_ENTRY_LABELS);
+    diff.table(2, &old.ear_links, &new.ear_links, EAR_LINK_LABELS);
+    diff.table(3, &old.unknown_table, &new.unknown_table, TABLE_ENTRY_LABELS);
+    diff.table(4, &old.model_ids, &new.model_ids, TABLE_ENTRY_LABELS);
+    diff.raw(5, &old.table_5, &new.table_5);
+    diff.table(6, &old.tpls_metadata, &new.tpls_metadata, TEXTURE_METADATA_LABELS);
+    diff.groups(7, &old.effects_0, &new.effects_0);
+    diff.groups(8, &old.effects_1, &new.effects_1);
+    diff.paths(&old.paths, &new.paths);
+    diff.raw(10, &old.table_10, &new.table_10);
+    diff.changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eff::sample;
+    use crate::eff::text::effect_fields;
+    use crate::eff::{CurvePoint, EarLink, Effect, TableEntry, TextureMetadata};
+    use serde_json::json;
+
+    fn changed(change: &Change) -> (&str, &str) {
+        (change.location.as_str(), change.field.as_deref().unwrap_or(""))
+    }
+
+    #[test]
+    fn tolerance() {
+        let exact = Tolerance::default();
+        assert!(exact.equal(1.5, 1.5));
+        assert!(!exact.equal(1.5, 1.5000001));
+
+        let absolute = Tolerance { absolute: 0.01, relative: 0.0 };
+        assert!(absolute.equal(100.0, 100.005));
+        assert!(!absolute.equal(100.0, 100.02));
+
+        // relativo ao maior dos dois valores
+        let relative = Tolerance { absolute: 0.0, relative: 0.001 };
+        assert!(relative.equal(1000.0, 1000.5));
+        assert!(!relative.equal(1000.0, 1002.0));
+        assert!(!relative.equal(0.0, 0.001));
+    }
+
+    #[test]
+    fn floats_within_tolerance() {
+        let old = sample::eff();
+        let mut new = sample::eff();
+        new.effects_0[0].effects[0].position.0 += 0.001;
+        new.effects_0[0].effects[0].time += 1;
+
+        let loose = Tolerance { absolute: 0.01, relative: 0.0 };
+        // inteiros sao sempre exatos
+        let changes = diff(&old, &new, loose);
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changed(&changes[0]), ("Effect 0, Group 0, Effect 0", "Delay"));
+
+        assert_eq!(diff(&old, &new, Tolerance::default()).len(), 2);
+    }
+
+    #[test]
+    fn non_finite_floats() {
+        let old = sample::eff();
+        let mut new = sample::eff();
+        new.paths[0].points[0].unknown = f32::NAN;
+        let changes = diff(&old, &new, Tolerance { absolute: 1.0, relative: 1.0 });
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].new, Some(json!("0x7FC00000")));
+
+        assert!(diff(&new, &new, Tolerance::default()).is_empty());
+    }
+
+    #[test]
+    fn txt2_labels() {
+        let old = sample::eff();
+        let mut new = sample::eff();
+        new.ear_links[0].id = 1;
+        new.tpls_metadata[1].texture_count = 2;
+        new.effects_1[0].unknown_x0a = 0xFF;
+        new.effects_1[0].effects[0].position.1 = 1.0;
+        new.effects_1[0].effects[0].rgba.3 = 0;
+        new.paths[0].points[1].handle_1.2 = 5.0;
+
+        let changes = diff(&old, &new, Tolerance::default());
+        let fields: Vec<_> = changes.iter().map(changed).collect();
+        assert_eq!(
+            fields,
+            [
+                ("Table 2 (EAR Links), Entry 0", "Effect Group"),
+                ("Table 6 (Texture Data), Entry 1", "Effect Texture Count"),
+                ("Effect 1, Group 0", "Offset[X0A]"),
+                ("Effect 1, Group 0, Effect 0", "Position Y"),
+                ("Effect 1, Group 0, Effect 0", "A"),
+                ("Table 9 (Paths), Path 0, Point 1", "Handle 1 Z"),
+            ]
+        );
+        assert_eq!(changes[3].old, Some(json!(50.0)));
+        assert_eq!(changes[3].new, Some(json!(1.0)));
+        assert_eq!(changes[3].to_string(), "~ Effect 1, Group 0, Effect 0: Position Y: 50.0 -> 1.0");
+    }
+
+    #[test]
+    fn added_and_removed() {
+        let old = sample::eff();
+        let mut new = sample::eff();
+        new.texture_ids.pop();
+        new.effects_0[0].effects.push(Effect::default());
+        new.table_5 = vec![1, 2];
+
+        let changes = diff(&old, &new, Tolerance::default());
+        let kinds: Vec<_> = changes.iter().map(|change| (change.kind, change.location.as_str())).collect();
+        assert_eq!(
+            kinds,
+            [
+                (ChangeKind::Removed, "Table 0 (TPL Texture IDs), Entry 1"),
+                (ChangeKind::Changed, "Table 5"),
+                (ChangeKind::Added, "Effect 0, Group 0, Effect 2"),
+            ]
+        );
+        assert_eq!(changes[0].old, Some(json!({ "id": 11, "unknown_a": 0x10B, "unknown_b": 0xAB00_000Bu32 })));
+        assert_eq!(changes[1].field.as_deref(), Some("bytes"));
+    }
+
+    // as tabelas de nomes cobrem todos os campos do JSON, e as do efeito batem com os txt2
+    #[test]
+    fn labels_cover_every_field() {
+        fn names<T: Serialize>(value: &T, labels: &[(&str, &[&str])], lists: &[&str]) {
+            let value = to_value(value);
+            let mut fields: Vec<_> = value.as_object().unwrap().keys().map(String::as_str).collect();
+            fields.retain(|field| !lists.contains(field));
+            let mut labelled: Vec<_> = labels.iter().map(|(name, _)| *name).collect();
+            fields.sort_unstable();
+            labelled.sort_unstable();
+            assert_eq!(fields, labelled);
+        }
+        names(&TableEntry::default(), TABLE_ENTRY_LABELS, &[]);
+        names(&EarLink::default(), EAR_LINK_LABELS, &[]);
+        names(&TextureMetadata::default(), TEXTURE_METADATA_LABELS, &[]);
+        names(&EffectGroup::default(), EFFECT_GROUP_LABELS, &["effects"]);
+        names(&Effect::default(), EFFECT_LABELS, &[]);
+        names(&CurvePoint::default(), CURVE_POINT_LABELS, &[]);
+
+        let txt2: Vec<_> = effect_fields(&Effect::default()).into_iter().map(|(label, _)| label).collect();
+        let labels: Vec<_> = EFFECT_LABELS.iter().flat_map(|(_, labels)| labels.iter().copied()).collect();
+        assert_eq!(txt2, labels);
+    }
+}
@@ -0,0 +1,216 @@
+use schemars::{json_schema, JsonSchema, Schema, SchemaGenerator};
+use serde::de::{self, Deserializer, Visitor};
+use serde::{Deserialize, Serialize, Serializer};
+use std::borrow::Cow;
+use std::fmt;
+use std::str::FromStr;
+
+// Enum de um campo numerico do arquivo. Valores sem nome ficam em Unknown(n), assim
+// nada se perde no repack. No txt2 e no json os valores conhecidos aparecem pelo nome e
+// os desconhecidos pelo numero; na leitura os dois sao aceitos.
+macro_rules! named_enum {
+    (
+        $(#[$meta:meta])*
+        $name:ident($repr:ty) { $($(#[$variant_meta:meta])* $variant:ident = $value:literal),* $(,)? }
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub enum $name {
+            $($(#[$variant_meta])* $variant,)*
+            /// A value without a known name, kept as is.
+            Unknown($repr),
+        }
+
+        impl $name {
+            /// Names of the known values, as written to the txt2 and JSON files.
+            pub const NAMES: &'static [&'static str] = &[$(stringify!($variant)),*];
+            const VALUES: &'static [$repr] = &[$($value),*];
+
+            pub fn from_value(value: $repr) -> $name {
+                $(if value == $value {
+                    return $name::$variant;
+                })*
+                $name::Unknown(value)
+            }
+
+            pub fn value(self) -> $repr {
+                match self {
+                    $($name::$variant => $value,)*
+                    $name::Unknown(value) => value,
+                }
+            }
+
+            /// `false` for values without a name, which the game may not handle.
+            pub fn is_known(self) -> bool {
+                !matches!($name::from_value(self.value()), $name::Unknown(_))
+            }
+
+            // "one of A, B or a number", ou so "a number" quando nenhum valor tem nome
+            fn expected() -> String {
+                match $name::NAMES {
+                    [] => "a number".to_owned(),
+                    names => format!("one of {} or a number", names.join(", ")),
+                }
+            }
+
+            fn name(self) -> Option<&'static str> {
+                match $name::from_value(self.value()) {
+                    $($name::$variant => Some(stringify!($variant)),)*
+                    $name::Unknown(_) => None,
+                }
+            }
+        }
+
+        impl Default for $name {
+            fn default() -> $name {
+                $name::from_value(0)
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                match self.name() {
+                    Some(name) => f.write_str(name),
+                    None => write!(f, "{}", self.value()),
+                }
+            }
+        }
+
+        /// A name, in any case, or a decimal or "0x" hex number.
+        impl FromStr for $name {
+            type Err = String;
+
+            fn from_str(value: &str) -> std::result::Result<$name, String> {
+                let value = value.trim();
+                if let Some(name) = $name::NAMES.iter().position(|name| name.eq_ignore_ascii_case(value)) {
+                    return Ok($name::from_value($name::VALUES[name]));
+                }
+                let number = match value.strip_prefix("0x").or_else(|| value.strip_prefix("0X")) {
+                    Some(digits) => <$repr>::from_str_radix(digits, 16),
+                    None => value.parse(),
+                };
+                number
+                    .map($name::from_value)
+                    .map_err(|_| format!("invalid {} \"{}\", expected {}", stringify!($name), value, $name::expected()))
+            }
+        }
+
+        impl Serialize for $name {
+            fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+                match self.name() {
+                    Some(name) => serializer.serialize_str(name),
+                    None => self.value().serialize(serializer),
+                }
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<$name, D::Error> {
+                struct NameOrNumber;
+
+                impl Visitor<'_> for NameOrNumber {
+                    type Value = $name;
+
+                    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                        f.write_str(&$name::expected())
+                    }
+
+                    fn visit_str<E: de::Error>(self, value: &str) -> std::result::Result<$name, E> {
+                        value.parse().map_err(E::custom)
+                    }
+
+                    fn visit_u64<E: de::Error>(self, value: u64) -> std::result::Result<$name, E> {
+                        <$repr>::try_from(value)
+                            .map($name::from_value)
+                            .map_err(|_| E::invalid_value(de::Unexpected::Unsigned(value), &self))
+                    }
+
+                    fn visit_i64<E: de::Error>(self, value: i64) -> std::result::Result<$name, E> {
+                        <$repr>::try_from(value)
+                            .map($name::from_value)
+                            .map_err(|_| E::invalid_value(de::Unexpected::Signed(value), &self))
+                    }
+                }
+
+                deserializer.deserialize_any(NameOrNumber)
+            }
+        }
+
+        impl JsonSchema for $name {
+            fn schema_name() -> Cow<'static, str> {
+                stringify!($name).into()
+            }
+
+            fn json_schema(_generator: &mut SchemaGenerator) -> Schema {
+                let maximum = <$repr>::MAX;
+                json_schema!({
+                    "anyOf": [
+                        { "type": "string", "enum": $name::NAMES },
+                        { "type": "integer", "minimum": 0, "maximum": maximum }
+                    ]
+                })
+            }
+        }
+    };
+}
+
+named_enum! {
+    /// How an effect follows its parent (Effect offset 0xC3).
+    SimulationType(u8) {
+        None = 0,
+        Normal = 1,
+        Offset = 2,
+        Replace = 3,
+    }
+}
+
+named_enum! {
+    /// Effect offset 0x108: an ESP particle, or a Control that drives other effects.
+    #[allow(clippy::upper_case_acronyms)]
+    EffectType(u8) {
+        ESP = 0,
+        Control = 1,
+    }
+}
+
+named_enum! {
+    /// Blend mode (Effect offset 0xC1), the game clamps it to 0xFF.
+    /// No mode has a confirmed name yet, so every value is written as a number;
+    /// names go here as they are identified.
+    Blend(u16) {}
+}
+
+impl Blend {
+    /// Largest value the game uses, higher values are clamped.
+    pub const MAX: u16 = 0xFF;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_and_numbers() {
+        assert_eq!("offset".parse::<SimulationType>().unwrap(), SimulationType::Offset);
+        assert_eq!("0x1".parse::<EffectType>().unwrap(), EffectType::Control);
+        assert_eq!(SimulationType::from_value(9).to_string(), "9");
+        assert!(!SimulationType::from_value(9).is_known());
+        assert_eq!(
+            "Ofset".parse::<SimulationType>().unwrap_err(),
+            "invalid SimulationType \"Ofset\", expected one of None, Normal, Offset, Replace or a number"
+        );
+    }
+
+    #[test]
+    fn blend() {
+        // nenhum modo tem nome ainda: todos os valores ficam em Unknown e sao escritos como numero
+        assert_eq!(Blend::from_value(1), Blend::Unknown(1));
+        assert_eq!(Blend::default().value(), 0);
+        assert_eq!("1".parse::<Blend>().unwrap().to_string(), "1");
+        assert_eq!("0x100".parse::<Blend>().unwrap().value(), 0x100);
+        assert_eq!("Add".parse::<Blend>().unwrap_err(), "invalid Blend \"Add\", expected a number");
+        assert_eq!(serde_json::to_string(&Blend::from_value(31)).unwrap(), "31");
+        assert_eq!(serde_json::from_str::<Blend>("31").unwrap(), Blend::Unknown(31));
+        assert!(serde_json::from_str::<Blend>("65536").is_err());
+    }
+}
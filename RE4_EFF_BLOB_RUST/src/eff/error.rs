@@ -0,0 +1,213 @@
+use super::Platform;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+pub type Result<T> = std::result::Result<T, EffError>;
+
+#[derive(Debug)]
+pub enum EffError {
+    /// I/O failure, with the file involved when it is known.
+    Io {
+        path: Option<PathBuf>,
+        source: std::io::Error,
+    },
+    /// The data ended before a value could be read.
+    Truncated { offset: u64 },
+    /// An element count that does not fit in the data that follows it.
+    BadCount { count: u64, offset: u64 },
+    /// The header does not list the 11 sections of the format.
+    BadSectionCount { count: u64 },
+    /// The section count does not read as 0xB in either byte order.
+    UnknownByteOrder,
+    /// A .EFFBLOBTXT written before the byte order was recorded in it, or a folder without one.
+    MissingByteOrder { path: PathBuf },
+    /// A txt2 value that could not be parsed.
+    Parse {
+        path: PathBuf,
+        line: usize,
+        field: String,
+        value: String,
+    },
+    /// A txt2 block title, such as "Effect 3", used twice in the same file.
+    Duplicate {
+        path: PathBuf,
+        line: usize,
+        name: String,
+    },
+    /// A txt2 field, or numbered block, that is missing.
+    MissingField {
+        path: PathBuf,
+        line: usize,
+        field: String,
+    },
+    /// A gap in the numbering of the "Effect Group N Data.txt2" files of a folder.
+    MissingGroup { path: PathBuf, index: usize },
+    /// The group files of an effect folder and the entries of its table disagree.
+    GroupCountMismatch {
+        folder: String,
+        groups: usize,
+        table: String,
+        entries: usize,
+    },
+    /// A JSON document that does not match the `Eff` model.
+    Json {
+        path: Option<PathBuf>,
+        source: serde_json::Error,
+    },
+    /// A CSV file that could not be read or written.
+    Csv {
+        path: Option<PathBuf>,
+        source: csv::Error,
+    },
+    /// A byte order (given or detected) that is not the one of the platform.
+    PlatformByteOrder { platform: Platform },
+    /// Table 5 or Table 10 has data and the byte order changes; they are raw bytes, in the
+    /// order they were read in, so they cannot be converted. `big` is the new byte order.
+    RawTables { big: bool },
+    /// `Eff::validate` found errors, tables that do not fit together.
+    Invalid { errors: usize },
+    /// A value too large for the binary field it is written to.
+    Overflow { field: String, value: u64 },
+    /// Byte order conversion failed (unknown host byte order).
+    Cast,
+    /// An error wrapped with the place it happened, e.g. "Effect 1", "Group 3".
+    Context {
+        context: String,
+        source: Box<EffError>,
+    },
+}
+
+impl EffError {
+    pub(crate) fn io(path: &Path, source: std::io::Error) -> EffError {
+        EffError::Io {
+            path: Some(path.to_path_buf()),
+            source,
+        }
+    }
+
+    pub fn context<C: Into<String>>(self, context: C) -> EffError {
+        EffError::Context {
+            context: context.into(),
+            source: Box::new(self),
+        }
+    }
+}
+
+impl fmt::Display for EffError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EffError::Io { path: Some(path), source } => {
+                write!(f, "I/O error on \"{}\": {}", path.display(), source)
+            }
+            EffError::Io { path: None, source } => write!(f, "I/O error: {}", source),
+            EffError::Truncated { offset } => {
+                write!(f, "unexpected end of data at offset 0x{:X}", offset)
+            }
+            EffError::BadCount { count, offset } => write!(
+                f,
+                "count {} does not fit in the data left after offset 0x{:X}",
+                count, offset
+            ),
+            EffError::BadSectionCount { count } => write!(
+                f,
+                "header lists {} sections, expected 11 (is the byte order right?)",
+                count
+            ),
+            EffError::UnknownByteOrder => write!(
+                f,
+                "unable to detect the byte order, the header does not start with the section count 0xB"
+            ),
+            EffError::MissingByteOrder { path } => write!(
+                f,
+                "\"{}\" is missing or does not record the byte order (extracted by an older version), it has to be given explicitly",
+                path.display()
+            ),
+            EffError::Parse { path, line, field, value } => write!(
+                f,
+                "{}:{}: invalid value \"{}\" for field \"{}\"",
+                path.display(),
+                line,
+                value,
+                field
+            ),
+            EffError::Duplicate { path, line, name } => write!(
+                f,
+                "{}:{}: \"{}\" appears more than once",
+                path.display(),
+                line,
+                name
+            ),
+            EffError::MissingField { path, line, field } => write!(
+                f,
+                "{}:{}: missing field \"{}\"",
+                path.display(),
+                line,
+                field
+            ),
+            EffError::MissingGroup { path, index } => write!(
+                f,
+                "\"{}\": missing \"Effect Group {} Data.txt2\", group files must be numbered from 0 without gaps",
+                path.display(),
+                index
+            ),
+            EffError::GroupCountMismatch { folder, groups, table, entries } => write!(
+                f,
+                "\"{}\" has {} group files but \"{}\" has {} entries, each group needs one entry",
+                folder, groups, table, entries
+            ),
+            EffError::Json { path: Some(path), source } => {
+                write!(f, "{}: {}", path.display(), source)
+            }
+            EffError::Json { path: None, source } => write!(f, "JSON: {}", source),
+            EffError::Csv { path: Some(path), source } => {
+                write!(f, "{}: {}", path.display(), source)
+            }
+            EffError::Csv { path: None, source } => write!(f, "CSV: {}", source),
+            EffError::PlatformByteOrder { platform } => write!(
+                f,
+                "{} files are {} endian, the byte order does not match",
+                platform,
+                if platform.is_big() { "big" } else { "little" }
+            ),
+            EffError::RawTables { big } => write!(
+                f,
+                "Table 5 and Table 10 are raw bytes in the byte order they were read in, they cannot be converted to {} endian",
+                if *big { "big" } else { "little" }
+            ),
+            EffError::Invalid { errors } => write!(f, "validation found {} error(s), the file was not written", errors),
+            EffError::Overflow { field, value } => {
+                write!(f, "{} value {} does not fit in the file format", field, value)
+            }
+            EffError::Cast => write!(f, "unable to convert value to the requested byte order"),
+            EffError::Context { context, source } => write!(f, "{}: {}", context, source),
+        }
+    }
+}
+
+impl std::error::Error for EffError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            EffError::Io { source, .. } => Some(source),
+            EffError::Json { source, .. } => Some(source),
+            EffError::Csv { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for EffError {
+    fn from(source: std::io::Error) -> EffError {
+        EffError::Io { path: None, source }
+    }
+}
+
+/// Adds location context to an error while it travels up the call stack.
+pub(crate) trait ResultExt<T> {
+    fn with_context<C: Into<String>, F: FnOnce() -> C>(self, context: F) -> Result<T>;
+}
+
+impl<T> ResultExt<T> for Result<T> {
+    fn with_context<C: Into<String>, F: FnOnce() -> C>(self, context: F) -> Result<T> {
+        self.map_err(|error| error.context(context()))
+    }
+}
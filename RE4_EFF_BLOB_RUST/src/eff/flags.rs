@@ -0,0 +1,182 @@
+use super::reader::FromHex;
+use bitflags::{bitflags, Flags};
+use schemars::{json_schema, JsonSchema, Schema, SchemaGenerator};
+use serde::de::{self, Deserializer, Visitor};
+use serde::{Deserialize, Serialize, Serializer};
+use std::borrow::Cow;
+use std::fmt;
+use std::str::FromStr;
+
+// Nenhum bit tem nome confirmado ainda. Quando um bit for identificado, basta
+// adicionar "const NOME = 0x..;" no tipo correspondente: o txt2 e o json passam a
+// mostrar o nome e continuam aceitando o numero. Bits sem nome sao mantidos (const _ = !0).
+bitflags! {
+    /// Effect offset 0x08.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+    pub struct EffectFlags: u32 {
+        const _ = !0;
+    }
+
+    /// Effect offset 0x10A, always little endian.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+    pub struct ControlFlags: u16 {
+        const _ = !0;
+    }
+
+    /// Effect offset 0x12B.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+    pub struct ControlPathFlags: u8 {
+        const _ = !0;
+    }
+}
+
+// Texto das flags: "LOOP | BILLBOARD | 0x400", nomes conhecidos e depois os bits sem
+// nome em hex; sem nenhum bit é "0x0". Na leitura cada parte pode ser um nome, um
+// numero hex "0x.." ou decimal, entao os arquivos antigos continuam validos.
+macro_rules! flags_text {
+    ($name:ident($repr:ty)) => {
+        impl $name {
+            // bits que tem nome
+            fn named_bits() -> $repr {
+                <$name as Flags>::FLAGS
+                    .iter()
+                    .filter(|flag| flag.is_named())
+                    .fold(0, |bits, flag| bits | flag.value().bits())
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                let mut parts: Vec<String> = self.iter_names().map(|(name, _)| name.to_owned()).collect();
+                let unnamed = self.bits() & !$name::named_bits();
+                if unnamed != 0 || parts.is_empty() {
+                    parts.push(format!("0x{:X}", unnamed));
+                }
+                f.write_str(&parts.join(" | "))
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = String;
+
+            fn from_str(value: &str) -> std::result::Result<$name, String> {
+                value.split('|').map(str::trim).try_fold($name::empty(), |flags, part| {
+                    let bits = match $name::from_name(&part.to_ascii_uppercase()) {
+                        Some(flag) => flag.bits(),
+                        None => <$repr>::from_hex(part)
+                            .or_else(|| part.parse().ok())
+                            .ok_or_else(|| format!("invalid {} \"{}\"", stringify!($name), part))?,
+                    };
+                    Ok(flags | $name::from_bits_retain(bits))
+                })
+            }
+        }
+
+        impl Serialize for $name {
+            fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+                serializer.collect_str(self)
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<$name, D::Error> {
+                struct TextOrNumber;
+
+                impl Visitor<'_> for TextOrNumber {
+                    type Value = $name;
+
+                    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                        f.write_str("a list of flags such as \"NAME | 0x400\", or a number")
+                    }
+
+                    fn visit_str<E: de::Error>(self, value: &str) -> std::result::Result<$name, E> {
+                        value.parse().map_err(E::custom)
+                    }
+
+                    fn visit_u64<E: de::Error>(self, value: u64) -> std::result::Result<$name, E> {
+                        <$repr>::try_from(value)
+                            .map($name::from_bits_retain)
+                            .map_err(|_| E::invalid_value(de::Unexpected::Unsigned(value), &self))
+                    }
+                }
+
+                deserializer.deserialize_any(TextOrNumber)
+            }
+        }
+
+        impl JsonSchema for $name {
+            fn schema_name() -> Cow<'static, str> {
+                stringify!($name).into()
+            }
+
+            fn json_schema(_generator: &mut SchemaGenerator) -> Schema {
+                let maximum = <$repr>::MAX;
+                json_schema!({
+                    "anyOf": [
+                        { "type": "string", "description": "Flag names and hex values separated by \"|\"" },
+                        { "type": "integer", "minimum": 0, "maximum": maximum }
+                    ]
+                })
+            }
+        }
+    };
+}
+
+flags_text!(EffectFlags(u32));
+flags_text!(ControlFlags(u16));
+flags_text!(ControlPathFlags(u8));
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    bitflags! {
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        struct Named: u32 {
+            const LOOP = 0x1;
+            const BILLBOARD = 0x2;
+            const _ = !0;
+        }
+    }
+    flags_text!(Named(u32));
+
+    #[test]
+    fn list_form() {
+        let flags = Named::LOOP | Named::BILLBOARD | Named::from_bits_retain(0x400);
+        assert_eq!(flags.to_string(), "LOOP | BILLBOARD | 0x400");
+        assert_eq!("LOOP | BILLBOARD | 0x400".parse::<Named>().unwrap(), flags);
+        assert_eq!(" loop|0x400 | billboard ".parse::<Named>().unwrap(), flags);
+        assert_eq!(Named::LOOP.to_string(), "LOOP");
+        assert_eq!(Named::empty().to_string(), "0x0");
+    }
+
+    #[test]
+    fn raw_numbers() {
+        // arquivos antigos: hex ou decimal, os bits com nome aparecem pelo nome na escrita
+        let flags = "0x403".parse::<Named>().unwrap();
+        assert_eq!(flags, Named::LOOP | Named::BILLBOARD | Named::from_bits_retain(0x400));
+        assert_eq!(flags.to_string(), "LOOP | BILLBOARD | 0x400");
+        assert_eq!("1027".parse::<Named>().unwrap(), flags);
+        assert_eq!("0x401".parse::<EffectFlags>().unwrap().bits(), 0x401);
+        assert_eq!(EffectFlags::from_bits_retain(0x401).to_string(), "0x401");
+        assert_eq!("5".parse::<ControlFlags>().unwrap().bits(), 5);
+        assert_eq!("0xFF".parse::<ControlPathFlags>().unwrap().bits(), 0xFF);
+    }
+
+    #[test]
+    fn invalid() {
+        assert!("SPIN".parse::<Named>().is_err());
+        assert!("LOOP |".parse::<Named>().is_err());
+        assert!("0x100".parse::<ControlPathFlags>().is_err());
+        assert!("256".parse::<ControlPathFlags>().is_err());
+    }
+
+    #[test]
+    fn json() {
+        let flags = Named::LOOP | Named::from_bits_retain(0x400);
+        assert_eq!(serde_json::to_string(&flags).unwrap(), "\"LOOP | 0x400\"");
+        assert_eq!(serde_json::from_str::<Named>("\"LOOP | 0x400\"").unwrap(), flags);
+        assert_eq!(serde_json::from_str::<Named>("1025").unwrap(), flags);
+        assert!(serde_json::from_str::<ControlPathFlags>("256").is_err());
+    }
+}
@@ -0,0 +1,131 @@
+// floats do documento JSON: o JSON nao tem NaN nem infinito, entao esses valores sao gravados
+// como o padrao de bits em hex ("0x7FC00000"), sem perder o payload do NaN
+use schemars::{json_schema, JsonSchema, Schema, SchemaGenerator};
+use serde::de::{self, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::borrow::Cow;
+use std::fmt;
+
+/// f32 written as a JSON number, or as its bit pattern in a "0x7FC00000" string when it is
+/// NaN or infinite. Both forms are accepted on reading.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Float(pub f32);
+
+pub(crate) type Float3 = (Float, Float, Float);
+pub(crate) type Float4 = (Float, Float, Float, Float);
+
+impl Serialize for Float {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if self.0.is_finite() {
+            serializer.serialize_f32(self.0)
+        } else {
+            serializer.serialize_str(&format!("0x{:08X}", self.0.to_bits()))
+        }
+    }
+}
+
+struct FloatVisitor;
+
+impl Visitor<'_> for FloatVisitor {
+    type Value = Float;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a number or the bit pattern of a float, as \"0x7FC00000\"")
+    }
+
+    fn visit_f64<E: de::Error>(self, value: f64) -> Result<Float, E> {
+        Ok(Float(value as f32))
+    }
+
+    fn visit_i64<E: de::Error>(self, value: i64) -> Result<Float, E> {
+        Ok(Float(value as f32))
+    }
+
+    fn visit_u64<E: de::Error>(self, value: u64) -> Result<Float, E> {
+        Ok(Float(value as f32))
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<Float, E> {
+        value
+            .strip_prefix("0x")
+            .or_else(|| value.strip_prefix("0X"))
+            .and_then(|digits| u32::from_str_radix(digits, 16).ok())
+            .map(|bits| Float(f32::from_bits(bits)))
+            .ok_or_else(|| E::invalid_value(de::Unexpected::Str(value), &self))
+    }
+}
+
+impl<'de> Deserialize<'de> for Float {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Float, D::Error> {
+        deserializer.deserialize_any(FloatVisitor)
+    }
+}
+
+impl JsonSchema for Float {
+    fn schema_name() -> Cow<'static, str> {
+        "Float".into()
+    }
+
+    fn json_schema(_generator: &mut SchemaGenerator) -> Schema {
+        json_schema!({
+            "description": "A number, or the bit pattern of a NaN or infinite float, as \"0x7FC00000\".",
+            "anyOf": [
+                { "type": "number" },
+                { "type": "string", "pattern": "^0[xX][0-9A-Fa-f]{1,8}$" }
+            ]
+        })
+    }
+}
+
+// campos f32 e tuplas de f32, convertidos de e para Float
+pub(crate) trait Floats: Sized {
+    type Encoded: Serialize + for<'de> Deserialize<'de>;
+
+    fn encode(&self) -> Self::Encoded;
+    fn decode(encoded: Self::Encoded) -> Self;
+}
+
+impl Floats for f32 {
+    type Encoded = Float;
+
+    fn encode(&self) -> Float {
+        Float(*self)
+    }
+
+    fn decode(encoded: Float) -> f32 {
+        encoded.0
+    }
+}
+
+impl Floats for (f32, f32, f32) {
+    type Encoded = Float3;
+
+    fn encode(&self) -> Float3 {
+        (Float(self.0), Float(self.1), Float(self.2))
+    }
+
+    fn decode(encoded: Float3) -> (f32, f32, f32) {
+        (encoded.0 .0, encoded.1 .0, encoded.2 .0)
+    }
+}
+
+impl Floats for (f32, f32, f32, f32) {
+    type Encoded = Float4;
+
+    fn encode(&self) -> Float4 {
+        (Float(self.0), Float(self.1), Float(self.2), Float(self.3))
+    }
+
+    fn decode(encoded: Float4) -> (f32, f32, f32, f32) {
+        (encoded.0 .0, encoded.1 .0, encoded.2 .0, encoded.3 .0)
+    }
+}
+
+// usados com #[serde(with = "float")] nos campos f32 dos tipos do Eff
+pub(crate) fn serialize<T: Floats, S: Serializer>(value: &T, serializer: S) -> Result<S::Ok, S::Error> {
+    value.encode().serialize(serializer)
+}
+
+pub(crate) fn deserialize<'de, T: Floats, D: Deserializer<'de>>(deserializer: D) -> Result<T, D::Error> {
+    T::Encoded::deserialize(deserializer).map(T::decode)
+}
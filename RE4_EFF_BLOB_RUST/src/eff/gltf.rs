@@ -0,0 +1,386 @@
+use super::diff::to_value;
+use super::manifest::TOOL_VERSION;
+use super::{Curve, Eff, EffError, Effect, EffectGroup, Result, SECTION_NAMES};
+use serde_json::{json, Value};
+use std::path::Path;
+
+// mesma escala do .obj dos txt2: 1/100, com Y sendo a altura (o glTF tambem é Y para cima)
+const SCALE: f32 = 0.01;
+// a speed é por frame; a linha mostra o caminho de 30 frames para que seja visivel
+const SPEED_FRAMES: f32 = 30.0;
+// pontos de cada segmento de um path
+const CURVE_SAMPLES: usize = 16;
+
+const MODE_LINES: u32 = 1;
+const MODE_LINE_STRIP: u32 = 3;
+const ARRAY_BUFFER: u32 = 34962;
+const ELEMENT_ARRAY_BUFFER: u32 = 34963;
+const FLOAT: u32 = 5126;
+const UNSIGNED_SHORT: u32 = 5123;
+
+// indices dos materiais, na ordem de materials()
+const SPAWN_BOX_MATERIAL: usize = 0;
+const SPEED_MATERIAL: usize = 1;
+const PATH_MATERIAL: usize = 2;
+
+// arestas de uma caixa, com os cantos na ordem de spawn_box
+const BOX_EDGES: [u16; 24] = [0, 1, 1, 3, 3, 2, 2, 0, 4, 5, 5, 7, 7, 6, 6, 4, 0, 4, 1, 5, 2, 6, 3, 7];
+
+fn scaled(value: (f32, f32, f32)) -> [f32; 3] {
+    [value.0 * SCALE, value.1 * SCALE, value.2 * SCALE]
+}
+
+fn is_finite(points: &[[f32; 3]]) -> bool {
+    points.iter().flatten().all(|value| value.is_finite())
+}
+
+fn materials() -> Value {
+    let material = |name: &str, color: [f32; 4]| {
+        json!({
+            "name": name,
+            "pbrMetallicRoughness": { "baseColorFactor": color, "metallicFactor": 0.0 },
+        })
+    };
+    json!([
+        material("Spawn Box", [1.0, 0.8, 0.0, 1.0]),
+        material("Speed", [1.0, 0.1, 0.1, 1.0]),
+        material("Path", [0.0, 0.8, 1.0, 1.0]),
+    ])
+}
+
+// cantos de position ± random, relativos a position (o node ja tem a translacao)
+fn spawn_box(effect: &Effect) -> Vec<[f32; 3]> {
+    let [x, y, z] = scaled(effect.random);
+    let mut corners = Vec::with_capacity(8);
+    for z in [-z, z] {
+        for y in [-y, y] {
+            for x in [-x, x] {
+                corners.push([x, y, z]);
+            }
+        }
+    }
+    corners
+}
+
+#[derive(Default)]
+struct Builder {
+    nodes: Vec<Value>,
+    meshes: Vec<Value>,
+    accessors: Vec<Value>,
+    buffer_views: Vec<Value>,
+    buffer: Vec<u8>,
+}
+
+impl Builder {
+    fn view(&mut self, bytes: &[u8], target: u32) -> usize {
+        // os accessors precisam de offsets alinhados ao tamanho do componente
+        self.buffer.resize(self.buffer.len().next_multiple_of(4), 0);
+        self.buffer_views.push(json!({
+            "buffer": 0,
+            "byteOffset": self.buffer.len(),
+            "byteLength": bytes.len(),
+            "target": target,
+        }));
+        self.buffer.extend_from_slice(bytes);
+        self.buffer_views.len() - 1
+    }
+
+    fn positions(&mut self, points: &[[f32; 3]]) -> usize {
+        let bytes: Vec<u8> = points.iter().flatten().flat_map(|value| value.to_le_bytes()).collect();
+        let view = self.view(&bytes, ARRAY_BUFFER);
+        let mut min = [f32::MAX; 3];
+        let mut max = [f32::MIN; 3];
+        for point in points {
+            for axis in 0..3 {
+                min[axis] = min[axis].min(point[axis]);
+                max[axis] = max[axis].max(point[axis]);
+            }
+        }
+        self.accessors.push(json!({
+            "bufferView": view,
+            "componentType": FLOAT,
+            "count": points.len(),
+            "type": "VEC3",
+            "min": min,
+            "max": max,
+        }));
+        self.accessors.len() - 1
+    }
+
+    fn indices(&mut self, indices: &[u16]) -> usize {
+        let bytes: Vec<u8> = indices.iter().flat_map(|index| index.to_le_bytes()).collect();
+        let view = self.view(&bytes, ELEMENT_ARRAY_BUFFER);
+        self.accessors.push(json!({
+            "bufferView": view,
+            "componentType": UNSIGNED_SHORT,
+            "count": indices.len(),
+            "type": "SCALAR",
+        }));
+        self.accessors.len() - 1
+    }
+
+    // None = nenhuma primitiva, o node fica sem mesh
+    fn mesh(&mut self, name: &str, primitives: Vec<Value>) -> Option<usize> {
+        if primitives.is_empty() {
+            return None;
+        }
+        self.meshes.push(json!({ "name": name, "primitives": primitives }));
+        Some(self.meshes.len() - 1)
+    }
+
+    fn node(&mut self, mut node: Value, mesh: Option<usize>, children: Vec<usize>) -> usize {
+        if let Some(mesh) = mesh {
+            node["mesh"] = json!(mesh);
+        }
+        if !children.is_empty() {
+            node["children"] = json!(children);
+        }
+        self.nodes.push(node);
+        self.nodes.len() - 1
+    }
+
+    fn effect(&mut self, name: &str, effect: &Effect) -> usize {
+        let mut primitives = Vec::new();
+        let corners = spawn_box(effect);
+        if effect.random != (0.0, 0.0, 0.0) && is_finite(&corners) {
+            primitives.push(json!({
+                "attributes": { "POSITION": self.positions(&corners) },
+                "indices": self.indices(&BOX_EDGES),
+                "mode": MODE_LINES,
+                "material": SPAWN_BOX_MATERIAL,
+            }));
+        }
+        let [x, y, z] = scaled(effect.speed);
+        let speed = [[0.0; 3], [x * SPEED_FRAMES, y * SPEED_FRAMES, z * SPEED_FRAMES]];
+        if effect.speed != (0.0, 0.0, 0.0) && is_finite(&speed) {
+            primitives.push(json!({
+                "attributes": { "POSITION": self.positions(&speed) },
+                "mode": MODE_LINES,
+                "material": SPEED_MATERIAL,
+            }));
+        }
+        let mesh = self.mesh(name, primitives);
+
+        let mut node = json!({ "name": name, "extras": to_value(effect) });
+        let translation = scaled(effect.position);
+        if is_finite(&[translation]) {
+            node["translation"] = json!(translation);
+        }
+        self.node(node, mesh, Vec::new())
+    }
+
+    fn group(&mut self, table: usize, index: usize, group: &EffectGroup) -> usize {
+        let name = format!("{}, Group {}", SECTION_NAMES[table], index);
+        let children = group
+            .effects
+            .iter()
+            .enumerate()
+            .map(|(effect_index, effect)| {
+                let effect_name = format!(
+                    "{}, Effect {}, EspID 0x{:X}, TextureID 0x{:X}",
+                    name, effect_index, effect.esp_id, effect.texture_id
+                );
+                self.effect(&effect_name, effect)
+            })
+            .collect();
+        // os campos do cabecalho do grupo; os efeitos estao nos nodes filhos
+        let mut extras = to_value(group);
+        if let Some(fields) = extras.as_object_mut() {
+            fields.remove("effects");
+        }
+        self.node(json!({ "name": name, "extras": extras }), None, children)
+    }
+
+    fn curve(&mut self, index: usize, curve: &Curve) -> usize {
+        let name = format!("Path {}", index);
+        let samples: Vec<[f32; 3]> = curve.sample(CURVE_SAMPLES).into_iter().map(scaled).collect();
+        let mut primitives = Vec::new();
+        if samples.len() > 1 && is_finite(&samples) {
+            primitives.push(json!({
+                "attributes": { "POSITION": self.positions(&samples) },
+                "mode": MODE_LINE_STRIP,
+                "material": PATH_MATERIAL,
+            }));
+        }
+        let mesh = self.mesh(&name, primitives);
+        self.node(json!({ "name": name, "extras": to_value(curve) }), mesh, Vec::new())
+    }
+}
+
+/// glTF 2.0 scene of the file: a node for each group of Effect 0/1 with a child for each
+/// effect, at its `position` (scale 1/100, Y up), and a node for each path of Table 9.
+/// The fields of the groups and effects are in the `extras` of their nodes.
+///
+/// The lines drawn are the spawn box of an effect (`position ± random`, assumed to be the
+/// range on each axis), its `speed` over 30 frames and each path, sampled with
+/// [`Curve::sample`].
+///
+/// Returns the .gltf document, which refers to the binary buffer as `bin_uri`, and the buffer.
+pub fn to_gltf(eff: &Eff, bin_uri: &str) -> Result<(String, Vec<u8>)> {
+    let mut builder = Builder::default();
+    let mut roots = Vec::new();
+
+    for (table, groups) in [(7, &eff.effects_0), (8, &eff.effects_1)] {
+        let children = groups.iter().enumerate().map(|(index, group)| builder.group(table, index, group)).collect();
+        roots.push(builder.node(json!({ "name": SECTION_NAMES[table] }), None, children));
+    }
+    let children = eff.paths.iter().enumerate().map(|(index, curve)| builder.curve(index, curve)).collect();
+    roots.push(builder.node(json!({ "name": SECTION_NAMES[9] }), None, children));
+
+    let mut document = json!({
+        "asset": { "version": "2.0", "generator": format!("RE4_EFF_BLOB_RUST {}", TOOL_VERSION) },
+        "scene": 0,
+        "scenes": [{ "nodes": roots }],
+        "nodes": builder.nodes,
+        "materials": materials(),
+    });
+    // o glTF nao aceita listas vazias
+    if !builder.meshes.is_empty() {
+        document["meshes"] = json!(builder.meshes);
+        document["accessors"] = json!(builder.accessors);
+        document["bufferViews"] = json!(builder.buffer_views);
+        document["buffers"] = json!([{ "uri": bin_uri, "byteLength": builder.buffer.len() }]);
+    }
+
+    let text = serde_json::to_string_pretty(&document).map_err(|source| EffError::Json { path: None, source })?;
+    Ok((text, builder.buffer))
+}
+
+// o nome do .bin vai como uri relativa, entao espacos e acentos precisam de %XX
+fn encode_uri(name: &str) -> String {
+    name.bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => (byte as char).to_string(),
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
+}
+
+/// Writes `path` (.gltf) and the buffer next to it, with the same name and the .bin extension.
+pub fn write_to_gltf(eff: &Eff, path: &Path) -> Result<()> {
+    let bin_path = path.with_extension("bin");
+    let bin_uri = encode_uri(&bin_path.file_name().unwrap_or_default().to_string_lossy());
+    let (text, buffer) = to_gltf(eff, &bin_uri)?;
+    std::fs::write(path, text).map_err(|error| EffError::io(path, error))?;
+    if !buffer.is_empty() {
+        std::fs::write(&bin_path, buffer).map_err(|error| EffError::io(&bin_path, error))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eff::sample;
+
+    fn document(eff: &Eff) -> (Value, Vec<u8>) {
+        let (text, buffer) = to_gltf(eff, "core.bin").unwrap();
+        (serde_json::from_str(&text).unwrap(), buffer)
+    }
+
+    fn node<'a>(document: &'a Value, name: &str) -> &'a Value {
+        let nodes = document["nodes"].as_array().unwrap();
+        nodes.iter().find(|node| node["name"] == name).unwrap_or_else(|| panic!("no node {}", name))
+    }
+
+    // nodes filhos de `parent`, pelo nome
+    fn children(document: &Value, parent: &str) -> Vec<String> {
+        let children = node(document, parent)["children"].as_array().cloned().unwrap_or_default();
+        children
+            .iter()
+            .map(|child| document["nodes"][child.as_u64().unwrap() as usize]["name"].as_str().unwrap().to_owned())
+            .collect()
+    }
+
+    #[test]
+    fn nodes() {
+        let (document, _) = document(&sample::eff());
+        assert_eq!(document["nodes"].as_array().unwrap().len(), 9);
+        assert_eq!(document["scenes"][0]["nodes"].as_array().unwrap().len(), 3);
+        assert_eq!(children(&document, "Effect 0"), ["Effect 0, Group 0"]);
+        assert_eq!(
+            children(&document, "Effect 0, Group 0"),
+            [
+                "Effect 0, Group 0, Effect 0, EspID 0x1, TextureID 0x0",
+                "Effect 0, Group 0, Effect 1, EspID 0x2, TextureID 0x1",
+            ]
+        );
+        assert_eq!(children(&document, "Effect 1, Group 0"), ["Effect 1, Group 0, Effect 0, EspID 0x3, TextureID 0x1"]);
+        assert_eq!(children(&document, "Table 9 (Paths)"), ["Path 0"]);
+
+        let effect = node(&document, "Effect 0, Group 0, Effect 1, EspID 0x2, TextureID 0x1");
+        assert_eq!(effect["translation"], json!(scaled((100.0, 50.0, -25.5))));
+        assert_eq!(document["asset"]["version"], "2.0");
+    }
+
+    #[test]
+    fn extras() {
+        let eff = sample::eff();
+        let (document, _) = document(&eff);
+        let effect = node(&document, "Effect 1, Group 0, Effect 0, EspID 0x3, TextureID 0x1");
+        assert_eq!(effect["extras"], to_value(&eff.effects_1[0].effects[0]));
+        assert_eq!(effect["extras"]["esp_id"], 3);
+
+        // o grupo tem os campos do cabecalho, sem a lista de efeitos
+        let group = &node(&document, "Effect 0, Group 0")["extras"];
+        assert_eq!(group["unknown_x02"], 1);
+        assert_eq!(group["unknown_x25"], json!([0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10]));
+        assert!(group.get("effects").is_none());
+
+        assert_eq!(node(&document, "Path 0")["extras"], to_value(&eff.paths[0]));
+    }
+
+    #[test]
+    fn buffers() {
+        let (document, buffer) = document(&sample::eff());
+        // 3 efeitos com caixa (8 cantos + 24 indices) e speed (2 pontos), e o path com 16 segmentos
+        let accessors = document["accessors"].as_array().unwrap();
+        let counts: Vec<u64> = accessors.iter().map(|accessor| accessor["count"].as_u64().unwrap()).collect();
+        assert_eq!(counts, [8, 24, 2, 8, 24, 2, 8, 24, 2, 17]);
+        assert_eq!(document["meshes"].as_array().unwrap().len(), 4);
+
+        for accessor in accessors {
+            let view = &document["bufferViews"][accessor["bufferView"].as_u64().unwrap() as usize];
+            let size = match (accessor["type"].as_str().unwrap(), accessor["componentType"].as_u64().unwrap()) {
+                ("VEC3", 5126) => 12,
+                ("SCALAR", 5123) => 2,
+                other => panic!("{:?}", other),
+            };
+            assert_eq!(view["byteLength"].as_u64().unwrap(), accessor["count"].as_u64().unwrap() * size);
+            let offset = view["byteOffset"].as_u64().unwrap();
+            assert_eq!(offset % 4, 0);
+            assert!(offset + view["byteLength"].as_u64().unwrap() <= buffer.len() as u64);
+        }
+        assert_eq!(document["buffers"], json!([{ "uri": "core.bin", "byteLength": buffer.len() }]));
+    }
+
+    #[test]
+    fn non_finite_values_are_skipped() {
+        let mut eff = sample::eff();
+        let effects = &mut eff.effects_0[0].effects;
+        effects[0].position.1 = f32::NAN;
+        effects[0].random.0 = f32::INFINITY;
+        effects[1].speed.2 = f32::NEG_INFINITY;
+        eff.paths[0].points[1].point.0 = f32::NAN;
+        let (document, _) = document(&eff);
+
+        let first = node(&document, "Effect 0, Group 0, Effect 0, EspID 0x1, TextureID 0x0");
+        assert!(first.get("translation").is_none());
+        // so a linha da speed sobra
+        let mesh = &document["meshes"][first["mesh"].as_u64().unwrap() as usize];
+        assert_eq!(mesh["primitives"].as_array().unwrap().len(), 1);
+        assert_eq!(mesh["primitives"][0]["material"], SPEED_MATERIAL);
+
+        let second = node(&document, "Effect 0, Group 0, Effect 1, EspID 0x2, TextureID 0x1");
+        let mesh = &document["meshes"][second["mesh"].as_u64().unwrap() as usize];
+        assert_eq!(mesh["primitives"].as_array().unwrap().len(), 1);
+        assert_eq!(mesh["primitives"][0]["material"], SPAWN_BOX_MATERIAL);
+
+        assert!(node(&document, "Path 0").get("mesh").is_none());
+        for accessor in document["accessors"].as_array().unwrap() {
+            for value in accessor["min"].as_array().into_iter().chain(accessor["max"].as_array()).flatten() {
+                assert!(value.as_f64().is_some_and(f64::is_finite), "{}", accessor);
+            }
+        }
+    }
+}
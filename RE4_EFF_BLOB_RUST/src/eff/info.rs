@@ -0,0 +1,114 @@
+use super::{Eff, EffectGroup, Result, SECTION_NAMES};
+use crate::eff::error::ResultExt;
+use scalar_types::Endian;
+use serde::Serialize;
+use std::fmt;
+use std::io::Cursor;
+
+/// A section listed in the header. `offset` is 0 for an empty section; `size` goes up to
+/// the next section (or the end of the file), so it includes the alignment padding.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SectionInfo {
+    pub index: usize,
+    pub name: String,
+    pub offset: u32,
+    pub size: u64,
+}
+
+/// Structural summary of a .EFFBLOB/.EFFBLOBBIG, shown by the "info" command.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Info {
+    pub size: u64,
+    pub endianness: String,
+    pub sections: Vec<SectionInfo>,
+    pub texture_ids: usize,
+    pub effect_0_indexes: usize,
+    pub ear_links: usize,
+    pub path_ids: usize,
+    pub model_ids: usize,
+    pub texture_data: usize,
+    pub effect_0_groups: usize,
+    pub effect_0_effects: usize,
+    pub effect_1_groups: usize,
+    pub effect_1_effects: usize,
+    pub paths: usize,
+    pub path_points: usize,
+}
+
+fn effect_count(groups: &[EffectGroup]) -> usize {
+    groups.iter().map(|group| group.effects.len()).sum()
+}
+
+impl Info {
+    /// Reads the header offsets and the contents of `bytes`, a whole file.
+    pub fn new(bytes: &[u8], endianness: &Endian<()>) -> Result<Info> {
+        let offsets = Eff::load_offsets(&mut Cursor::new(bytes), endianness).with_context(|| "Header")?;
+        let eff = Eff::load(&mut Cursor::new(bytes), endianness)?;
+
+        let file_size = bytes.len() as u64;
+        let sections = SECTION_NAMES
+            .iter()
+            .zip(&offsets)
+            .enumerate()
+            .map(|(index, (name, offset))| {
+                // a secao termina onde comeca a proxima
+                let start = *offset as u64;
+                let end = offsets
+                    .iter()
+                    .map(|offset| *offset as u64)
+                    .filter(|offset| *offset > start)
+                    .min()
+                    .unwrap_or(file_size)
+                    .min(file_size);
+                SectionInfo {
+                    index,
+                    name: (*name).to_owned(),
+                    offset: *offset,
+                    size: if start == 0 { 0 } else { end.saturating_sub(start) },
+                }
+            })
+            .collect();
+
+        Ok(Info {
+            size: file_size,
+            endianness: if endianness.is_big() { "Big" } else { "Little" }.to_owned(),
+            sections,
+            texture_ids: eff.texture_ids.len(),
+            effect_0_indexes: eff.core_ids.len(),
+            ear_links: eff.ear_links.len(),
+            path_ids: eff.unknown_table.len(),
+            model_ids: eff.model_ids.len(),
+            texture_data: eff.tpls_metadata.len(),
+            effect_0_groups: eff.effects_0.len(),
+            effect_0_effects: effect_count(&eff.effects_0),
+            effect_1_groups: eff.effects_1.len(),
+            effect_1_effects: effect_count(&eff.effects_1),
+            paths: eff.paths.len(),
+            path_points: eff.paths.iter().map(|path| path.points.len()).sum(),
+        })
+    }
+}
+
+impl fmt::Display for Info {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Size: {} bytes", self.size)?;
+        writeln!(f, "Endianness: {}", self.endianness)?;
+        writeln!(f, "Sections:")?;
+        for section in &self.sections {
+            if section.offset == 0 {
+                writeln!(f, "  {:<27} empty", section.name)?;
+            } else {
+                writeln!(f, "  {:<27} offset 0x{:X}, size 0x{:X}", section.name, section.offset, section.size)?;
+            }
+        }
+        writeln!(f, "Texture IDs: {}", self.texture_ids)?;
+        writeln!(f, "Effect 0 Indexes: {}", self.effect_0_indexes)?;
+        writeln!(f, "EAR Links: {}", self.ear_links)?;
+        writeln!(f, "Effect Path IDs: {}", self.path_ids)?;
+        writeln!(f, "BIN Model IDs: {}", self.model_ids)?;
+        writeln!(f, "Texture Data: {}", self.texture_data)?;
+        writeln!(f, "Effect 0: {} groups, {} effects", self.effect_0_groups, self.effect_0_effects)?;
+        writeln!(f, "Effect 1: {} groups, {} effects", self.effect_1_groups, self.effect_1_effects)?;
+        write!(f, "Paths: {} paths, {} points", self.paths, self.path_points)
+    }
+}
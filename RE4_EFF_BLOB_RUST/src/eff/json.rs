@@ -0,0 +1,83 @@
+use super::{Eff, EffError, Result};
+use std::path::Path;
+
+/// The whole `Eff` as one JSON document. Tuples, such as positions, are arrays
+/// and Table 5/Table 10 are arrays of bytes.
+pub fn to_json(eff: &Eff) -> Result<String> {
+    serde_json::to_string_pretty(eff).map_err(|source| EffError::Json { path: None, source })
+}
+
+pub fn from_json(json: &str) -> Result<Eff> {
+    serde_json::from_str(json).map_err(|source| EffError::Json { path: None, source })
+}
+
+pub fn write_to_json(eff: &Eff, path: &Path) -> Result<()> {
+    std::fs::write(path, to_json(eff)?).map_err(|error| EffError::io(path, error))
+}
+
+pub fn read_from_json(path: &Path) -> Result<Eff> {
+    let json = std::fs::read_to_string(path).map_err(|error| EffError::io(path, error))?;
+    serde_json::from_str(&json).map_err(|source| EffError::Json {
+        path: Some(path.to_path_buf()),
+        source,
+    })
+}
+
+/// JSON Schema (draft 2020-12) of the documents written by [`to_json`].
+pub fn schema() -> String {
+    let schema = schemars::schema_for!(Eff);
+    serde_json::to_string_pretty(&schema).expect("a schema always serializes")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eff::sample;
+
+    #[test]
+    fn round_trip() {
+        let eff = sample::eff();
+        assert_eq!(from_json(&to_json(&eff).unwrap()).unwrap(), eff);
+    }
+
+    #[test]
+    fn non_finite_floats() {
+        let mut eff = sample::eff();
+        let nan = f32::from_bits(0x7FC0_1234);
+        eff.effects_0[0].effects[0].position = (nan, f32::INFINITY, f32::NEG_INFINITY);
+        eff.effects_1[0].unknown_x0c = -f32::NAN;
+        eff.paths[0].points[1].unknown = nan;
+
+        let json = to_json(&eff).unwrap();
+        assert!(json.contains("\"0x7FC01234\""));
+        assert!(json.contains("\"0x7F800000\""));
+        assert!(json.contains("\"0xFF800000\""));
+        assert!(!json.contains("null"));
+
+        // o NaN nao é igual a ele mesmo, a comparacao é pelos bytes
+        let back = from_json(&json).unwrap();
+        let endianness = scalar_types::Endian::Little(());
+        assert_eq!(back.compile(&endianness).unwrap(), eff.compile(&endianness).unwrap());
+        assert_eq!(back.effects_0[0].effects[0].position.0.to_bits(), 0x7FC0_1234);
+    }
+
+    #[test]
+    fn float_forms() {
+        let json = to_json(&sample::eff()).unwrap();
+        let json = json.replacen("\"width\": 64.0", "\"width\": \"0x42800000\"", 1);
+        assert_eq!(from_json(&json).unwrap().effects_0[0].effects[0].width, 64.0);
+        let json = json.replacen("\"height\": 64.0", "\"height\": 64", 1);
+        assert_eq!(from_json(&json).unwrap().effects_0[0].effects[0].height, 64.0);
+        assert!(from_json(&json.replacen("\"grow\": 0.0", "\"grow\": \"NaN\"", 1)).is_err());
+        assert!(from_json(&json.replacen("\"grow\": 0.0", "\"grow\": null", 1)).is_err());
+    }
+
+    #[test]
+    fn schema_accepts_bit_patterns() {
+        let schema: serde_json::Value = serde_json::from_str(&schema()).unwrap();
+        let float = &schema["$defs"]["Float"]["anyOf"];
+        assert_eq!(float[0]["type"], "number");
+        assert_eq!(float[1]["type"], "string");
+        assert_eq!(schema["$defs"]["CurvePoint"]["properties"]["unknown"]["$ref"], "#/$defs/Float");
+    }
+}
@@ -0,0 +1,234 @@
+use super::reader::{BlockReader, TextFile};
+use super::text::{group_files, write_file};
+use super::{Eff, Platform, Result, SECTION_NAMES};
+use scalar_types::Endian;
+use sha2::{Digest, Sha256};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Version of the txt2 folder layout written by this tool.
+/// 1 = banner only .EFFBLOBTXT (up to 1.1.0), 2 = manifest, raw tables 5/10,
+/// group header bytes 0x25 and table unknowns.
+pub const LAYOUT_VERSION: u32 = 2;
+
+pub const TOOL_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Contents of the `<name>.EFFBLOBTXT` file written next to the extracted folder.
+#[derive(Debug, PartialEq)]
+pub struct Manifest {
+    pub tool_version: String,
+    pub layout_version: u32,
+    /// Byte order of the original file, `None` for files extracted by older versions.
+    pub endianness: Option<Endian<()>>,
+    /// Release the file came from, when it was given on the extraction (`--platform`).
+    pub platform: Option<Platform>,
+    /// Indexes of the sections that had data in the original file.
+    pub sections: Vec<usize>,
+    pub effect_0_groups: Option<usize>,
+    pub effect_1_groups: Option<usize>,
+    /// SHA-256 of the original file, in lowercase hex.
+    pub source_sha256: Option<String>,
+}
+
+fn endianness_name(endianness: &Endian<()>) -> &'static str {
+    if endianness.is_big() {
+        "Big"
+    } else {
+        "Little"
+    }
+}
+
+fn section_present(eff: &Eff, index: usize) -> bool {
+    match index {
+        0 => !eff.texture_ids.is_empty(),
+        1 => !eff.core_ids.is_empty(),
+        2 => !eff.ear_links.is_empty(),
+        3 => !eff.unknown_table.is_empty(),
+        4 => !eff.model_ids.is_empty(),
+        5 => !eff.table_5.is_empty(),
+        6 => !eff.tpls_metadata.is_empty(),
+        7 => !eff.effects_0.is_empty(),
+        8 => !eff.effects_1.is_empty(),
+        9 => !eff.paths.is_empty(),
+        _ => !eff.table_10.is_empty(),
+    }
+}
+
+// arquivo txt2 que so existe quando a secao tem dados
+fn section_file(index: usize) -> Option<&'static str> {
+    match index {
+        5 => Some("Tables/Table_5_Raw.txt2"),
+        10 => Some("Tables/Table_10_Raw.txt2"),
+        _ => None,
+    }
+}
+
+/// Path of the manifest of the extracted folder `path`: the folder name with the
+/// `.EFFBLOBTXT` extension, next to it ("core_001" -> "core_001.EFFBLOBTXT").
+// components() drops a trailing separator, so "core_001/" still gives "core_001.EFFBLOBTXT"
+pub fn manifest_path(path: &Path) -> PathBuf {
+    let mut file = path.components().as_path().as_os_str().to_owned();
+    file.push(".EFFBLOBTXT");
+    PathBuf::from(file)
+}
+
+/// SHA-256 of `bytes` as 64 lowercase hex digits, the form kept in the manifest as
+/// `source_sha256` to recognize the original file.
+pub fn sha256_hex(bytes: &[u8]) -> String {
+    Sha256::digest(bytes).iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+impl Manifest {
+    /// Manifest for `eff` extracted from a file in `endianness` byte order;
+    /// `source` is the original file, when available, to record its hash.
+    pub fn new(eff: &Eff, endianness: &Endian<()>, source: Option<&[u8]>) -> Manifest {
+        Manifest {
+            tool_version: TOOL_VERSION.to_owned(),
+            layout_version: LAYOUT_VERSION,
+            endianness: Some(if endianness.is_big() { Endian::Big(()) } else { Endian::Little(()) }),
+            platform: None,
+            sections: (0..SECTION_NAMES.len()).filter(|index| section_present(eff, *index)).collect(),
+            effect_0_groups: Some(eff.effects_0.len()),
+            effect_1_groups: Some(eff.effects_1.len()),
+            source_sha256: source.map(sha256_hex),
+        }
+    }
+
+    /// Writes `<path>.EFFBLOBTXT`, `path` being the extracted folder.
+    pub fn write(&self, path: &Path) -> Result<()> {
+        let mut file = Vec::<u8>::new();
+        file.write_all(b"# RE4_EFF_BLOB_RUST\n")?;
+        file.write_all(b"# Tool By Zatarita\n")?;
+        file.write_all(b"# Fork By JADERLINK\n")?;
+        file.write_all(format!("# Version {}\n", self.tool_version).as_bytes())?;
+        file.write_all(format!("Tool Version: {}\n", self.tool_version).as_bytes())?;
+        file.write_all(format!("Layout Version: {}\n", self.layout_version).as_bytes())?;
+        if let Some(endianness) = &self.endianness {
+            file.write_all(format!("Endianness: {}\n", endianness_name(endianness)).as_bytes())?;
+        }
+        if let Some(platform) = self.platform {
+            file.write_all(format!("Platform: {}\n", platform).as_bytes())?;
+        }
+        let sections: Vec<String> = self.sections.iter().map(|index| index.to_string()).collect();
+        file.write_all(format!("Sections: {}\n", sections.join(", ")).as_bytes())?;
+        if let Some(count) = self.effect_0_groups {
+            file.write_all(format!("Effect 0 Groups: {}\n", count).as_bytes())?;
+        }
+        if let Some(count) = self.effect_1_groups {
+            file.write_all(format!("Effect 1 Groups: {}\n", count).as_bytes())?;
+        }
+        if let Some(hash) = &self.source_sha256 {
+            file.write_all(format!("Source SHA-256: {}\n", hash).as_bytes())?;
+        }
+
+        write_file(&manifest_path(path), &file)
+    }
+
+    /// Manifest of a folder extracted by an older version: layout version 1 and no other
+    /// information. Used for banner-only `.EFFBLOBTXT` files and folders without one.
+    pub fn legacy() -> Manifest {
+        Manifest {
+            tool_version: String::new(),
+            layout_version: 1,
+            endianness: None,
+            platform: None,
+            sections: Vec::new(),
+            effect_0_groups: None,
+            effect_1_groups: None,
+            source_sha256: None,
+        }
+    }
+
+    /// Reads `<path>.EFFBLOBTXT`. Files written by older versions only have the banner,
+    /// they give [`Manifest::legacy`].
+    pub fn read(path: &Path) -> Result<Manifest> {
+        let file = TextFile::open(&manifest_path(path))?;
+        let reader = BlockReader::new(&file.path, file.header());
+        let mut manifest = Manifest::legacy();
+
+        for field in reader.fields() {
+            match field.key.as_str() {
+                "Tool Version" => manifest.tool_version = field.value.clone(),
+                "Layout Version" => manifest.layout_version = reader.parse_dec(field)?,
+                "Endianness" => {
+                    manifest.endianness = match field.value.as_str() {
+                        "Little" => Some(Endian::Little(())),
+                        "Big" => Some(Endian::Big(())),
+                        _ => return Err(reader.parse_error(field)),
+                    }
+                }
+                "Platform" => manifest.platform = Some(reader.parse_dec(field)?),
+                "Sections" => {
+                    manifest.sections = field
+                        .value
+                        .split(',')
+                        .map(str::trim)
+                        .filter(|index| !index.is_empty())
+                        .map(|index| index.parse().map_err(|_| reader.parse_error(field)))
+                        .collect::<Result<_>>()?
+                }
+                "Effect 0 Groups" => manifest.effect_0_groups = Some(reader.parse_dec(field)?),
+                "Effect 1 Groups" => manifest.effect_1_groups = Some(reader.parse_dec(field)?),
+                "Source SHA-256" => manifest.source_sha256 = Some(field.value.clone()),
+                // campos desconhecidos, de versoes mais novas, sao ignorados
+                _ => {}
+            }
+        }
+
+        Ok(manifest)
+    }
+
+    /// Compares the manifest with the extracted folder at `path`.
+    /// Returns one warning per difference, the folder can still be repacked.
+    pub fn check(&self, path: &Path) -> Vec<String> {
+        let mut warnings = Vec::new();
+
+        if self.layout_version < LAYOUT_VERSION {
+            warnings.push(format!(
+                "text layout version {} is older than {}, fields added since then use default values",
+                self.layout_version, LAYOUT_VERSION
+            ));
+        } else if self.layout_version > LAYOUT_VERSION {
+            warnings.push(format!(
+                "text layout version {} is newer than this tool supports ({})",
+                self.layout_version, LAYOUT_VERSION
+            ));
+        }
+
+        if let (Some(platform), Some(endianness)) = (self.platform, &self.endianness) {
+            if platform.is_big() != endianness.is_big() {
+                warnings.push(format!(
+                    "platform {} is {} endian, but the manifest lists {} endian",
+                    platform,
+                    if platform.is_big() { "big" } else { "little" },
+                    endianness_name(endianness).to_lowercase()
+                ));
+            }
+        }
+
+        for (folder, count) in [("Effect 0", self.effect_0_groups), ("Effect 1", self.effect_1_groups)] {
+            let Some(count) = count else { continue };
+            let found = group_files(&path.join(folder)).map_or(0, |files| files.len());
+            if found != count {
+                warnings.push(format!(
+                    "\"{}\" has {} group files, the manifest lists {}",
+                    folder, found, count
+                ));
+            }
+        }
+
+        for index in &self.sections {
+            if let Some(file) = section_file(*index) {
+                if !path.join(file).is_file() {
+                    warnings.push(format!(
+                        "{} is listed in the manifest but \"{}\" is missing",
+                        SECTION_NAMES.get(*index).unwrap_or(&"Unknown section"),
+                        file
+                    ));
+                }
+            }
+        }
+
+        warnings
+    }
+}
@@ -0,0 +1,293 @@
+use super::diff::to_value;
+use super::{Eff, EffError, Result, SECTION_NAMES};
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::fmt;
+
+// campos do Eff na ordem das secoes do arquivo, para os nomes em SECTION_NAMES
+const SECTION_FIELDS: [&str; 0xB] = [
+    "texture_ids",
+    "core_ids",
+    "ear_links",
+    "unknown_table",
+    "model_ids",
+    "table_5",
+    "tpls_metadata",
+    "effects_0",
+    "effects_1",
+    "paths",
+    "table_10",
+];
+
+/// Version kept when both sides changed the same value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Side {
+    #[default]
+    Ours,
+    Theirs,
+}
+
+/// A value changed differently by both sides. `None` is an item (group, effect, table
+/// entry, path point) that does not exist on that side.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Conflict {
+    pub location: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub field: Option<String>,
+    pub base: Option<Value>,
+    pub ours: Option<Value>,
+    pub theirs: Option<Value>,
+}
+
+impl fmt::Display for Conflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = |value: &Option<Value>| value.as_ref().map_or_else(|| "none".to_owned(), Value::to_string);
+        write!(f, "{}", self.location)?;
+        if let Some(field) = &self.field {
+            write!(f, ": {}", field)?;
+        }
+        write!(f, ": base {}, ours {}, theirs {}", text(&self.base), text(&self.ours), text(&self.theirs))
+    }
+}
+
+// nome dos itens de cada lista, como no diff: "Effect 0, Group 2, Effect 5"
+fn item_name(field: &str) -> &'static str {
+    match field {
+        "effects_0" | "effects_1" => "Group",
+        "effects" => "Effect",
+        "paths" => "Path",
+        "points" => "Point",
+        _ => "Entry",
+    }
+}
+
+// lista de structs, mesclada item por item; as outras listas (tuplas, bytes) sao um valor so
+fn is_list(values: [&Vec<Value>; 3]) -> bool {
+    values.iter().any(|items| items.iter().any(Value::is_object))
+}
+
+struct Merge {
+    prefer: Side,
+    conflicts: Vec<Conflict>,
+}
+
+impl Merge {
+    fn conflict(&mut self, location: &str, field: Option<&str>, values: [Option<&Value>; 3]) {
+        let [base, ours, theirs] = values.map(|value| value.cloned());
+        self.conflicts.push(Conflict {
+            location: location.to_owned(),
+            field: field.map(str::to_owned),
+            base,
+            ours,
+            theirs,
+        });
+    }
+
+    // o lado que mudou ganha; mudancas diferentes nos dois lados sao um conflito
+    fn pick(&mut self, location: &str, field: Option<&str>, values: [Option<&Value>; 3]) -> Option<Value> {
+        let [base, ours, theirs] = values;
+        if ours == theirs || theirs == base {
+            return ours.cloned();
+        }
+        if ours == base {
+            return theirs.cloned();
+        }
+        self.conflict(location, field, values);
+        match self.prefer {
+            Side::Ours => ours.cloned(),
+            Side::Theirs => theirs.cloned(),
+        }
+    }
+
+    fn value(&mut self, location: &str, field: Option<&str>, values: [&Value; 3]) -> Value {
+        match values {
+            [Value::Object(base), Value::Object(ours), Value::Object(theirs)] => {
+                Value::Object(self.object(location, [base, ours, theirs]))
+            }
+            [Value::Array(base), Value::Array(ours), Value::Array(theirs)] if is_list([base, ours, theirs]) => {
+                let item = item_name(field.unwrap_or_default());
+                Value::Array(self.list(location, item, [base, ours, theirs]))
+            }
+            _ => self
+                .pick(location, field, values.map(Some))
+                .expect("all three values exist"),
+        }
+    }
+
+    fn object(&mut self, location: &str, values: [&Map<String, Value>; 3]) -> Map<String, Value> {
+        let [base, ours, theirs] = values;
+        let mut merged = Map::new();
+        for (field, base_value) in base {
+            // os tres lados sao do mesmo tipo, entao tem os mesmos campos
+            if let (Some(ours_value), Some(theirs_value)) = (ours.get(field), theirs.get(field)) {
+                let value = self.value(location, Some(field), [base_value, ours_value, theirs_value]);
+                merged.insert(field.clone(), value);
+            }
+        }
+        merged
+    }
+
+    // itens pelo indice; adicionar ou remover no final de um lado só é aceito, mas um item
+    // depois de um removido mudaria de indice (e as referencias a ele), entao é um conflito
+    fn list(&mut self, location: &str, item: &str, values: [&Vec<Value>; 3]) -> Vec<Value> {
+        let length = values.iter().map(|items| items.len()).max().unwrap_or(0);
+        let mut merged = Vec::new();
+        for index in 0..length {
+            let name = format!("{}, {} {}", location, item, index);
+            let found = values.map(|items| items.get(index));
+            let value = match found {
+                [Some(base), Some(ours), Some(theirs)] => Some(self.value(&name, None, [base, ours, theirs])),
+                _ => self.pick(&name, None, found),
+            };
+            match value {
+                Some(value) if merged.len() == index => merged.push(value),
+                Some(_) => self.conflict(&name, None, found),
+                None => {}
+            }
+        }
+        merged
+    }
+}
+
+/// Three-way merge at field granularity: a table entry, group header field, effect field or
+/// path point changed by only one side is taken from it; changed by both (differently) is
+/// a [`Conflict`], resolved with `prefer`. Lists are matched by index, as in [`super::diff::diff`].
+pub fn merge(base: &Eff, ours: &Eff, theirs: &Eff, prefer: Side) -> Result<(Eff, Vec<Conflict>)> {
+    let mut merge = Merge {
+        prefer,
+        conflicts: Vec::new(),
+    };
+    let values = [base, ours, theirs].map(to_value);
+    let mut merged = Map::new();
+    for (table, field) in SECTION_FIELDS.iter().enumerate() {
+        let found = values.each_ref().map(|value| &value[field]);
+        let value = merge.value(SECTION_NAMES[table], Some(field), found);
+        merged.insert((*field).to_owned(), value);
+    }
+    let eff = serde_json::from_value(Value::Object(merged)).map_err(|source| EffError::Json { path: None, source })?;
+    Ok((eff, merge.conflicts))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eff::sample;
+    use crate::eff::Effect;
+    use serde_json::json;
+
+    #[test]
+    fn one_side_changes() {
+        let base = sample::eff();
+        let mut ours = sample::eff();
+        let mut theirs = sample::eff();
+        ours.effects_0[0].effects[0].time = 60;
+        theirs.effects_0[0].effects[0].width = 32.0;
+        theirs.texture_ids[1].id = 12;
+
+        let (merged, conflicts) = merge(&base, &ours, &theirs, Side::Ours).unwrap();
+        assert!(conflicts.is_empty());
+        assert_eq!(merged.effects_0[0].effects[0].time, 60);
+        assert_eq!(merged.effects_0[0].effects[0].width, 32.0);
+        assert_eq!(merged.texture_ids[1].id, 12);
+    }
+
+    #[test]
+    fn same_change_on_both_sides() {
+        let base = sample::eff();
+        let mut ours = sample::eff();
+        ours.paths[0].points[1].point.1 = 10.0;
+        let theirs = ours.clone();
+
+        let (merged, conflicts) = merge(&base, &ours, &theirs, Side::Theirs).unwrap();
+        assert!(conflicts.is_empty());
+        assert_eq!(merged.paths[0].points[1].point.1, 10.0);
+    }
+
+    #[test]
+    fn conflict_resolved_by_prefer() {
+        let base = sample::eff();
+        let mut ours = sample::eff();
+        let mut theirs = sample::eff();
+        ours.effects_1[0].effects[0].time = 10;
+        theirs.effects_1[0].effects[0].time = 20;
+
+        let (merged, conflicts) = merge(&base, &ours, &theirs, Side::Ours).unwrap();
+        assert_eq!(merged.effects_1[0].effects[0].time, 10);
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].location, "Effect 1, Group 0, Effect 0");
+        assert_eq!(conflicts[0].field.as_deref(), Some("time"));
+        assert_eq!(conflicts[0].base, Some(json!(30)));
+        assert_eq!(conflicts[0].ours, Some(json!(10)));
+        assert_eq!(conflicts[0].theirs, Some(json!(20)));
+
+        let (merged, _) = merge(&base, &ours, &theirs, Side::Theirs).unwrap();
+        assert_eq!(merged.effects_1[0].effects[0].time, 20);
+    }
+
+    #[test]
+    fn items_added_and_removed() {
+        let base = sample::eff();
+        let mut ours = sample::eff();
+        let mut theirs = sample::eff();
+        ours.effects_0[0].effects.push(Effect::default());
+        theirs.paths[0].points.pop();
+
+        let (merged, conflicts) = merge(&base, &ours, &theirs, Side::Ours).unwrap();
+        assert!(conflicts.is_empty());
+        assert_eq!(merged.effects_0[0].effects.len(), 3);
+        assert_eq!(merged.paths[0].points.len(), 1);
+    }
+
+    #[test]
+    fn removed_and_changed_is_a_conflict() {
+        let base = sample::eff();
+        let mut ours = sample::eff();
+        let mut theirs = sample::eff();
+        ours.texture_ids.pop();
+        theirs.texture_ids[1].unknown_a = 1;
+
+        let (merged, conflicts) = merge(&base, &ours, &theirs, Side::Ours).unwrap();
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].location, "Table 0 (TPL Texture IDs), Entry 1");
+        assert_eq!(conflicts[0].field, None);
+        assert_eq!(conflicts[0].ours, None);
+        assert_eq!(merged.texture_ids.len(), 1);
+
+        let (merged, _) = merge(&base, &ours, &theirs, Side::Theirs).unwrap();
+        assert_eq!(merged.texture_ids[1].unknown_a, 1);
+    }
+
+    #[test]
+    fn item_after_a_removed_one_is_a_conflict() {
+        let base = sample::eff();
+        let mut ours = sample::eff();
+        let mut theirs = sample::eff();
+        // o ours remove o efeito 1, o efeito 2 do theirs passaria a ser o 1
+        ours.effects_0[0].effects.pop();
+        theirs.effects_0[0].effects.push(Effect::default());
+
+        let (merged, conflicts) = merge(&base, &ours, &theirs, Side::Theirs).unwrap();
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].location, "Effect 0, Group 0, Effect 2");
+        assert_eq!(conflicts[0].base, None);
+        assert_eq!(merged.effects_0[0].effects.len(), 1);
+    }
+
+    #[test]
+    fn non_finite_floats() {
+        let mut base = sample::eff();
+        base.effects_0[0].effects[1].delta_speed = f32::NAN;
+        let mut ours = base.clone();
+        let mut theirs = base.clone();
+        ours.effects_0[0].effects[0].speed.1 = f32::INFINITY;
+        theirs.paths[0].points[0].unknown = f32::from_bits(0x7FC0_1234);
+        theirs.effects_0[0].unknown_x0c = f32::NEG_INFINITY;
+
+        let (merged, conflicts) = merge(&base, &ours, &theirs, Side::Ours).unwrap();
+        assert!(conflicts.is_empty());
+        assert!(merged.effects_0[0].effects[1].delta_speed.is_nan());
+        assert_eq!(merged.effects_0[0].effects[0].speed.1, f32::INFINITY);
+        assert_eq!(merged.paths[0].points[0].unknown.to_bits(), 0x7FC0_1234);
+        assert_eq!(merged.effects_0[0].unknown_x0c, f32::NEG_INFINITY);
+    }
+}
@@ -0,0 +1,231 @@
+use super::{Curve, EffError, Result};
+use std::fmt::Write as _;
+use std::path::Path;
+
+/// Points evaluated on each segment for the OBJ when no other resolution is given.
+pub const DEFAULT_SAMPLES: usize = 16;
+
+// cores dos paths no svg, repetidas a cada 8
+const COLORS: [&str; 8] = ["#e6194b", "#3cb44b", "#4363d8", "#f58231", "#911eb4", "#42d4f4", "#f032e6", "#9a6324"];
+// tamanho de cada vista do svg, em pixels
+const VIEW_SIZE: f32 = 400.0;
+const VIEW_MARGIN: f32 = 24.0;
+
+fn is_finite(curve: &Curve) -> bool {
+    curve
+        .points
+        .iter()
+        .flat_map(|point| [point.point, point.handle_0, point.handle_1])
+        .all(|(x, y, z)| x.is_finite() && y.is_finite() && z.is_finite())
+}
+
+/// OBJ with one object and one line element per path, scale 1/100 with Y as the height,
+/// as the .obj of the extracted effect groups. Each segment is evaluated at `samples` points,
+/// see [`Curve::sample`]; samples that are not finite are left out, and paths with less
+/// than two points have no line.
+pub fn to_obj(paths: &[Curve], samples: usize) -> String {
+    let mut obj = String::from("#For reference only, scale 1/100, Y is the height\n\n");
+    let mut vertex = 1;
+    for (index, curve) in paths.iter().enumerate() {
+        // um "v NaN" deixa o obj invalido nos editores
+        let points: Vec<_> = curve
+            .sample(samples)
+            .into_iter()
+            .filter(|(x, y, z)| x.is_finite() && y.is_finite() && z.is_finite())
+            .collect();
+        let _ = writeln!(obj, "o Path_{}", index);
+        for (x, y, z) in &points {
+            let _ = writeln!(obj, "v {} {} {}", x / 100.0, y / 100.0, z / 100.0);
+        }
+        if points.len() > 1 {
+            let indices: Vec<String> = (vertex..vertex + points.len()).map(|index| index.to_string()).collect();
+            let _ = writeln!(obj, "l {}", indices.join(" "));
+        }
+        obj.push('\n');
+        vertex += points.len();
+    }
+    obj
+}
+
+// uma vista do svg: o nome e os eixos do jogo que viram o x e o y da tela
+struct View {
+    name: &'static str,
+    horizontal: fn((f32, f32, f32)) -> f32,
+    vertical: fn((f32, f32, f32)) -> f32,
+}
+
+// o y do svg é para baixo, entao a altura (Y) entra negativa
+const VIEWS: [View; 3] = [
+    View { name: "Top (X, Z)", horizontal: |p| p.0, vertical: |p| p.2 },
+    View { name: "Side (Z, Y)", horizontal: |p| p.2, vertical: |p| -p.1 },
+    View { name: "Front (X, Y)", horizontal: |p| p.0, vertical: |p| -p.1 },
+];
+
+impl View {
+    fn project(&self, point: (f32, f32, f32)) -> (f32, f32) {
+        ((self.horizontal)(point), (self.vertical)(point))
+    }
+
+    // o box 3D projetado numa vista alinhada aos eixos é o box 2D da curva
+    fn bounds(&self, paths: &[Curve]) -> Option<[f32; 4]> {
+        let mut bounds: Option<[f32; 4]> = None;
+        for corners in paths.iter().filter_map(Curve::bounding_box) {
+            for corner in corners {
+                let (x, y) = self.project(corner);
+                if !x.is_finite() || !y.is_finite() {
+                    continue;
+                }
+                let [min_x, min_y, max_x, max_y] = bounds.get_or_insert([x, y, x, y]);
+                *min_x = min_x.min(x);
+                *min_y = min_y.min(y);
+                *max_x = max_x.max(x);
+                *max_y = max_y.max(y);
+            }
+        }
+        bounds
+    }
+
+    // cada segmento vira um "C" do svg: a projecao de uma Bezier é a Bezier dos pontos projetados
+    fn path_data(&self, curve: &Curve) -> String {
+        let mut data = String::new();
+        if let Some(first) = curve.points.first() {
+            let (x, y) = self.project(first.point);
+            let _ = write!(data, "M {} {}", x, y);
+        }
+        for pair in curve.points.windows(2) {
+            let [_, a, b, c] = pair[0].segment_to(&pair[1]).map(|point| self.project(point));
+            let _ = write!(data, " C {} {} {} {} {} {}", a.0, a.1, b.0, b.1, c.0, c.1);
+        }
+        data
+    }
+}
+
+/// SVG with the top, side and front views of every path, side by side, each one fitted to
+/// the paths. Segments are written as SVG cubic Bezier commands, in game units.
+pub fn to_svg(paths: &[Curve]) -> String {
+    let width = VIEW_SIZE * VIEWS.len() as f32;
+    let height = VIEW_SIZE + VIEW_MARGIN;
+    let mut svg = String::new();
+    let _ = writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{0}" height="{1}" viewBox="0 0 {0} {1}" font-family="sans-serif" font-size="14">"#,
+        width, height
+    );
+    for (column, view) in VIEWS.iter().enumerate() {
+        let left = column as f32 * VIEW_SIZE;
+        let _ = writeln!(svg, r#"  <text x="{}" y="{}">{}</text>"#, left + 8.0, VIEW_MARGIN - 8.0, view.name);
+        let _ = writeln!(
+            svg,
+            r##"  <rect x="{}" y="{}" width="{}" height="{}" fill="none" stroke="#999"/>"##,
+            left, VIEW_MARGIN, VIEW_SIZE, VIEW_SIZE
+        );
+        let Some([min_x, min_y, max_x, max_y]) = view.bounds(paths) else { continue };
+        // 5% de folga em volta; um path reto (largura 0) ainda precisa de uma area
+        let padding = ((max_x - min_x).max(max_y - min_y) * 0.05).max(1.0);
+        let _ = writeln!(
+            svg,
+            r#"  <svg x="{}" y="{}" width="{}" height="{}" viewBox="{} {} {} {}">"#,
+            left,
+            VIEW_MARGIN,
+            VIEW_SIZE,
+            VIEW_SIZE,
+            min_x - padding,
+            min_y - padding,
+            max_x - min_x + padding * 2.0,
+            max_y - min_y + padding * 2.0
+        );
+        // um NaN deixaria o "d" invalido e o svg nao abre
+        let drawn = paths.iter().enumerate().filter(|(_, curve)| curve.points.len() > 1 && is_finite(curve));
+        for (index, curve) in drawn {
+            let _ = writeln!(
+                svg,
+                r#"    <path d="{}" fill="none" stroke="{}" stroke-width="2" vector-effect="non-scaling-stroke"><title>Path {}</title></path>"#,
+                view.path_data(curve),
+                COLORS[index % COLORS.len()],
+                index
+            );
+        }
+        svg.push_str("  </svg>\n");
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+pub fn write_paths_to_obj(paths: &[Curve], samples: usize, path: &Path) -> Result<()> {
+    std::fs::write(path, to_obj(paths, samples)).map_err(|error| EffError::io(path, error))
+}
+
+pub fn write_paths_to_svg(paths: &[Curve], path: &Path) -> Result<()> {
+    std::fs::write(path, to_svg(paths)).map_err(|error| EffError::io(path, error))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eff::sample::point;
+
+    fn line(from: (f32, f32, f32), to: (f32, f32, f32)) -> Curve {
+        Curve {
+            points: vec![point(from.0, from.1, from.2), point(to.0, to.1, to.2)],
+        }
+    }
+
+    // linhas "v" e "l" do obj
+    fn elements(obj: &str, kind: &str) -> Vec<String> {
+        obj.lines()
+            .filter_map(|line| line.strip_prefix(kind))
+            .map(str::to_owned)
+            .collect()
+    }
+
+    #[test]
+    fn obj_indexes() {
+        let paths = [
+            line((0.0, 0.0, 0.0), (100.0, 0.0, 0.0)),
+            Curve { points: vec![point(50.0, 200.0, -300.0)] },
+            line((0.0, 0.0, 0.0), (0.0, 100.0, 0.0)),
+        ];
+        let obj = to_obj(&paths, 2);
+        assert_eq!(elements(&obj, "o "), ["Path_0", "Path_1", "Path_2"]);
+        assert_eq!(elements(&obj, "v ").len(), 7);
+        // o path de um ponto tem o vertice, mas nao tem linha; os indices continuam depois dele
+        assert_eq!(elements(&obj, "l "), ["1 2 3", "5 6 7"]);
+        assert_eq!(elements(&obj, "v ")[3], "0.5 2 -3");
+        assert_eq!(elements(&obj, "v ")[6], "0 1 0");
+    }
+
+    #[test]
+    fn obj_skips_non_finite() {
+        let mut bad = line((0.0, 0.0, 0.0), (100.0, 0.0, 0.0));
+        bad.points.push(point(f32::NAN, 0.0, 0.0));
+        let paths = [bad, line((0.0, 0.0, 0.0), (0.0, 0.0, 100.0))];
+        let obj = to_obj(&paths, 2);
+        assert!(!obj.contains("NaN") && !obj.contains("inf"), "{}", obj);
+        // o segmento com NaN some, o primeiro continua
+        assert_eq!(elements(&obj, "l "), ["1 2 3", "4 5 6"]);
+    }
+
+    #[test]
+    fn svg_view_bounds() {
+        let paths = [line((0.0, 0.0, 0.0), (100.0, 0.0, 0.0)), line((0.0, 0.0, 0.0), (0.0, 40.0, 0.0))];
+        let svg = to_svg(&paths);
+        let boxes: Vec<&str> = svg
+            .lines()
+            .filter(|line| line.starts_with("  <svg"))
+            .filter_map(|line| line.split("viewBox=\"").nth(1)?.split('"').next())
+            .collect();
+        // 5% de folga do maior lado; a vista lateral (Z, Y) só tem a altura
+        assert_eq!(boxes, ["-5 -5 110 10", "-2 -42 4 44", "-5 -45 110 50"]);
+        assert_eq!(svg.matches("<path ").count(), 6);
+
+        // um path com NaN nao é desenhado e nao muda as vistas
+        let mut with_nan = paths.to_vec();
+        with_nan.push(line((f32::NAN, 0.0, 0.0), (50.0, 0.0, 0.0)));
+        let other = to_svg(&with_nan);
+        assert_eq!(other.matches("<path ").count(), 6);
+        assert!(!other.contains("NaN"));
+        assert!(other.contains(r#"viewBox="-5 -5 110 10""#));
+
+        assert!(!to_svg(&[]).contains("<path"));
+    }
+}
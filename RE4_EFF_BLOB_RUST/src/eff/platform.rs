@@ -0,0 +1,153 @@
+use scalar_types::Endian;
+use std::fmt;
+use std::str::FromStr;
+
+/// Alignment of the sections and of the entries of the offset tables. The same on every
+/// platform known so far.
+pub const SECTION_ALIGNMENT: u64 = 0x20;
+
+/// The releases of RE4 that use EFFBLOB files. Everything the tool knows about a platform
+/// is here: when research finds a difference between them, it goes in these methods.
+///
+/// The Control Flag (Effect offset 0x10A) is little endian on all of them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Platform {
+    /// RE4 2007 (PC)
+    Pc2007,
+    Ps2,
+    /// RE4 UHD (PC)
+    Uhd,
+    Ps4,
+    /// Nintendo Switch
+    Ns,
+    /// GameCube
+    Gc,
+    Wii,
+    X360,
+}
+
+impl Platform {
+    pub const ALL: [Platform; 8] = [
+        Platform::Pc2007,
+        Platform::Ps2,
+        Platform::Uhd,
+        Platform::Ps4,
+        Platform::Ns,
+        Platform::Gc,
+        Platform::Wii,
+        Platform::X360,
+    ];
+
+    /// Name used by `--platform` and in the .EFFBLOBTXT.
+    pub fn name(self) -> &'static str {
+        match self {
+            Platform::Pc2007 => "2007",
+            Platform::Ps2 => "PS2",
+            Platform::Uhd => "UHD",
+            Platform::Ps4 => "PS4",
+            Platform::Ns => "NS",
+            Platform::Gc => "GC",
+            Platform::Wii => "Wii",
+            Platform::X360 => "X360",
+        }
+    }
+
+    pub fn is_big(self) -> bool {
+        matches!(self, Platform::Gc | Platform::Wii | Platform::X360)
+    }
+
+    pub fn endianness(self) -> Endian<()> {
+        if self.is_big() {
+            Endian::Big(())
+        } else {
+            Endian::Little(())
+        }
+    }
+
+    /// ".EFFBLOB" for the little endian releases, ".EFFBLOBBIG" for the big endian ones.
+    pub fn extension(self) -> &'static str {
+        if self.is_big() {
+            ".EFFBLOBBIG"
+        } else {
+            ".EFFBLOB"
+        }
+    }
+
+    pub fn alignment(self) -> u64 {
+        SECTION_ALIGNMENT
+    }
+}
+
+impl fmt::Display for Platform {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Platform {
+    type Err = String;
+
+    fn from_str(value: &str) -> std::result::Result<Platform, String> {
+        Platform::ALL
+            .into_iter()
+            .find(|platform| platform.name().eq_ignore_ascii_case(value.trim()))
+            .ok_or_else(|| {
+                let names: Vec<&str> = Platform::ALL.iter().map(|platform| platform.name()).collect();
+                format!("invalid platform \"{}\", expected one of {}", value, names.join(", "))
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eff::manifest::Manifest;
+    use crate::eff::sample;
+
+    #[test]
+    fn names() {
+        for platform in Platform::ALL {
+            assert_eq!(platform.to_string().to_lowercase().parse::<Platform>(), Ok(platform));
+        }
+        assert_eq!(" uhd ".parse::<Platform>(), Ok(Platform::Uhd));
+        assert!("ps3".parse::<Platform>().unwrap_err().contains("2007, PS2, UHD"));
+    }
+
+    #[test]
+    fn byte_order_and_extension() {
+        assert!(!Platform::Uhd.is_big());
+        assert_eq!(Platform::Uhd.extension(), ".EFFBLOB");
+        assert!(Platform::Wii.is_big());
+        assert!(Platform::Wii.endianness().is_big());
+        assert_eq!(Platform::Wii.extension(), ".EFFBLOBBIG");
+    }
+
+    #[test]
+    fn compile_for() {
+        let eff = sample::eff();
+        for platform in Platform::ALL {
+            let bytes = eff.compile_for(platform).unwrap();
+            assert_eq!(bytes, eff.compile(&platform.endianness()).unwrap());
+            // as secoes comecam alinhadas
+            for entry in bytes[4..0x30].chunks(4) {
+                let entry: [u8; 4] = entry.try_into().unwrap();
+                let offset = if platform.is_big() { u32::from_be_bytes(entry) } else { u32::from_le_bytes(entry) };
+                assert_eq!(offset as u64 % platform.alignment(), 0);
+            }
+        }
+    }
+
+    #[test]
+    fn recorded_in_the_manifest() {
+        let folder = std::env::temp_dir().join(format!("re4_effblob_platform_{}", std::process::id()));
+        std::fs::create_dir_all(&folder).unwrap();
+        let path = folder.join("core");
+        let eff = sample::eff();
+        let mut manifest = Manifest::new(&eff, &Endian::Big(()), None);
+        manifest.platform = Some(Platform::Gc);
+        manifest.write(&path).unwrap();
+        let read = Manifest::read(&path).unwrap();
+        std::fs::remove_dir_all(&folder).unwrap();
+        assert_eq!(read.platform, Some(Platform::Gc));
+    }
+}
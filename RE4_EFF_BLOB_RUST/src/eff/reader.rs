@@ -0,0 +1,393 @@
+use super::{EffError, Result};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+// Values written as "0x.." in the txt2 files
+pub(super) trait FromHex: Sized {
+    fn from_hex(value: &str) -> Option<Self>;
+}
+
+macro_rules! impl_from_hex {
+    ($($type:ty),*) => {
+        $(impl FromHex for $type {
+            fn from_hex(value: &str) -> Option<$type> {
+                let digits = value.strip_prefix("0x").or_else(|| value.strip_prefix("0X"))?;
+                <$type>::from_str_radix(digits, 16).ok()
+            }
+        })*
+    };
+}
+
+impl_from_hex!(u8, u16, u32);
+
+// titulos que iniciam um bloco, como "Effect 0" ou "Entry 1:"; "Point" fica dentro do bloco anterior
+const TITLES: [&str; 3] = ["Entry", "Effect", "Point"];
+const CHILD_TITLE: &str = "Point";
+
+// quantos campos ausentes sao listados em um aviso
+const MAX_LISTED: usize = 8;
+
+// linha "Campo: valor"
+pub(super) struct Field {
+    pub key: String,
+    pub value: String,
+    pub line: usize,
+}
+
+// campos depois de um titulo; o primeiro bloco do arquivo nao tem titulo
+pub(super) struct Block {
+    pub title: Option<(String, usize)>,
+    pub line: usize,
+    pub fields: Vec<Field>,
+    pub children: Vec<Block>,
+}
+
+impl Block {
+    fn new(title: Option<(String, usize)>, line: usize) -> Block {
+        Block {
+            title,
+            line,
+            fields: Vec::new(),
+            children: Vec::new(),
+        }
+    }
+
+    fn name(&self) -> String {
+        match &self.title {
+            Some((name, index)) => format!("{} {}", name, index),
+            None => "header".to_owned(),
+        }
+    }
+}
+
+fn parse_title(key: &str) -> Option<(String, usize)> {
+    let (name, index) = key.rsplit_once(' ')?;
+    if !TITLES.contains(&name) {
+        return None;
+    }
+    Some((name.to_owned(), index.parse().ok()?))
+}
+
+/// A txt2 file split in blocks of "Field: value" lines.
+/// Fields are found by name, so their order in a block does not matter; `#` starts a
+/// comment, blank lines, indentation, CRLF line ends and a UTF-8 BOM are ignored.
+pub(super) struct TextFile {
+    pub path: PathBuf,
+    pub blocks: Vec<Block>,
+}
+
+impl TextFile {
+    pub fn open(path: &Path) -> Result<TextFile> {
+        let text = std::fs::read_to_string(path).map_err(|error| EffError::io(path, error))?;
+        TextFile::parse(path, &text)
+    }
+
+    pub fn parse(path: &Path, text: &str) -> Result<TextFile> {
+        let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+        let mut blocks = vec![Block::new(None, 1)];
+
+        for (number, line) in text.lines().enumerate() {
+            let line_number = number + 1;
+            let line = match line.split_once('#') {
+                Some((content, _comment)) => content,
+                None => line,
+            }
+            .trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let (key, value) = match line.split_once(':') {
+                Some((key, value)) => (key.trim(), Some(value.trim())),
+                None => (line, None),
+            };
+
+            // "Entry 0: 0x5" é um campo, "Entry 0:" é um titulo
+            let title = match value {
+                None | Some("") => parse_title(key),
+                Some(_) => None,
+            };
+            let last = blocks.last_mut().expect("the header block always exists");
+
+            match (title, value) {
+                (Some(title), _) if title.0 == CHILD_TITLE && last.title.is_some() => {
+                    last.children.push(Block::new(Some(title), line_number))
+                }
+                (Some(title), _) => blocks.push(Block::new(Some(title), line_number)),
+                (None, Some(value)) => {
+                    let block = match last.children.last_mut() {
+                        Some(child) => child,
+                        None => last,
+                    };
+                    block.fields.push(Field {
+                        key: key.to_owned(),
+                        value: value.to_owned(),
+                        line: line_number,
+                    });
+                }
+                (None, None) => {
+                    return Err(EffError::Parse {
+                        path: path.to_path_buf(),
+                        line: line_number,
+                        field: "Field: value".to_owned(),
+                        value: line.to_owned(),
+                    })
+                }
+            }
+        }
+
+        Ok(TextFile {
+            path: path.to_path_buf(),
+            blocks,
+        })
+    }
+
+    pub fn header(&self) -> &Block {
+        &self.blocks[0]
+    }
+
+    pub fn titled(&self, name: &str, warnings: &mut Vec<String>) -> Result<Vec<&Block>> {
+        titled(&self.path, &self.blocks[1..], name, warnings)
+    }
+}
+
+/// Blocks titled `name`, ordered by their index. A repeated index or a gap in the
+/// numbering is an error, blocks with another title are reported as warnings.
+pub(super) fn titled<'a>(
+    path: &Path,
+    blocks: &'a [Block],
+    name: &str,
+    warnings: &mut Vec<String>,
+) -> Result<Vec<&'a Block>> {
+    let mut result = BTreeMap::<usize, &Block>::new();
+
+    for block in blocks {
+        match &block.title {
+            Some((title, index)) if title == name => {
+                if result.insert(*index, block).is_some() {
+                    return Err(EffError::Duplicate {
+                        path: path.to_path_buf(),
+                        line: block.line,
+                        name: block.name(),
+                    });
+                }
+            }
+            _ => warnings.push(format!(
+                "{}:{}: unexpected \"{}\", ignored",
+                path.display(),
+                block.line,
+                block.name()
+            )),
+        }
+    }
+
+    for (expected, (index, block)) in result.iter().enumerate() {
+        if *index != expected {
+            return Err(EffError::MissingField {
+                path: path.to_path_buf(),
+                line: block.line,
+                field: format!("{} {}", name, expected),
+            });
+        }
+    }
+
+    Ok(result.into_values().collect())
+}
+
+/// Reads the fields of a block by name. A missing field takes the default value and is
+/// reported by `finish`, together with fields that were never read.
+pub(super) struct BlockReader<'a> {
+    path: &'a Path,
+    block: &'a Block,
+    used: Vec<bool>,
+    missing: Vec<String>,
+}
+
+impl<'a> BlockReader<'a> {
+    pub fn new(path: &'a Path, block: &'a Block) -> BlockReader<'a> {
+        BlockReader {
+            path,
+            block,
+            used: vec![false; block.fields.len()],
+            missing: Vec::new(),
+        }
+    }
+
+    pub fn fields(&self) -> &'a [Field] {
+        &self.block.fields
+    }
+
+    /// Field `key`, without a warning when it is missing.
+    pub fn optional(&mut self, key: &str) -> Option<&'a Field> {
+        let index = self.block.fields.iter().position(|field| field.key == key)?;
+        self.used[index] = true;
+        Some(&self.block.fields[index])
+    }
+
+    pub fn dec<T: FromStr + Default>(&mut self, key: &str) -> Result<T> {
+        match self.optional(key) {
+            Some(field) => self.parse_dec(field),
+            None => {
+                self.missing.push(key.to_owned());
+                Ok(T::default())
+            }
+        }
+    }
+
+    pub fn hex<T: FromHex + Default>(&mut self, key: &str) -> Result<T> {
+        match self.optional(key) {
+            Some(field) => self.parse_hex(field),
+            None => {
+                self.missing.push(key.to_owned());
+                Ok(T::default())
+            }
+        }
+    }
+
+    pub fn dec_optional<T: FromStr>(&mut self, key: &str) -> Result<Option<T>> {
+        self.optional(key).map(|field| self.parse_dec(field)).transpose()
+    }
+
+    pub fn parse_dec<T: FromStr>(&self, field: &Field) -> Result<T> {
+        field.value.parse().map_err(|_| self.parse_error(field))
+    }
+
+    pub fn parse_hex<T: FromHex>(&self, field: &Field) -> Result<T> {
+        T::from_hex(&field.value).ok_or_else(|| self.parse_error(field))
+    }
+
+    /// Space separated hex bytes without prefix, as in "00 1F A0".
+    pub fn parse_bytes(&self, field: &Field) -> Result<Vec<u8>> {
+        field
+            .value
+            .split_whitespace()
+            .map(|byte| u8::from_str_radix(byte, 16).map_err(|_| self.parse_error(field)))
+            .collect()
+    }
+
+    pub fn parse_error(&self, field: &Field) -> EffError {
+        EffError::Parse {
+            path: self.path.to_path_buf(),
+            line: field.line,
+            field: field.key.clone(),
+            value: field.value.clone(),
+        }
+    }
+
+    pub fn warning(&self, message: &str) -> String {
+        format!("{}:{}: {}: {}", self.path.display(), self.block.line, self.block.name(), message)
+    }
+
+    pub fn finish(self, warnings: &mut Vec<String>) {
+        if !self.missing.is_empty() {
+            let mut listed = self.missing[..self.missing.len().min(MAX_LISTED)].join(", ");
+            if self.missing.len() > MAX_LISTED {
+                listed.push_str(&format!(" and {} more", self.missing.len() - MAX_LISTED));
+            }
+            warnings.push(self.warning(&format!("missing {}, using default values", listed)));
+        }
+
+        for (field, used) in self.block.fields.iter().zip(&self.used) {
+            if !used {
+                warnings.push(format!(
+                    "{}:{}: unknown or repeated field \"{}\", ignored",
+                    self.path.display(),
+                    field.line,
+                    field.key
+                ));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEXT: &str = "\u{feff}# cabecalho\r\nCount: 2\r\n\r\nEffect 1:\n  Blend: 0x1F  # comentario\n  ESP ID: 7\n\
+                        Effect 0:\nPoint 0:\n  X: 1.5\nPoint 1:\n  X: -2\n";
+
+    fn parse(text: &str) -> Result<TextFile> {
+        TextFile::parse(Path::new("test.txt2"), text)
+    }
+
+    #[test]
+    fn blocks_and_children() {
+        let file = parse(TEXT).unwrap();
+        assert_eq!(file.header().fields[0].key, "Count");
+        assert_eq!(file.header().fields[0].value, "2");
+
+        let mut warnings = Vec::new();
+        let effects = file.titled("Effect", &mut warnings).unwrap();
+        assert!(warnings.is_empty());
+        // ordenados pelo indice, nao pela ordem no arquivo
+        assert_eq!(effects[0].line, 7);
+        assert_eq!(effects[0].children.len(), 2);
+        assert_eq!(effects[0].children[1].fields[0].value, "-2");
+        assert_eq!(effects[1].fields[0].value, "0x1F");
+        assert_eq!(effects[1].fields[0].line, 5);
+    }
+
+    #[test]
+    fn fields_by_name() {
+        let file = parse(TEXT).unwrap();
+        let mut warnings = Vec::new();
+        let effects = file.titled("Effect", &mut warnings).unwrap();
+        let mut reader = BlockReader::new(&file.path, effects[1]);
+        assert_eq!(reader.dec::<u8>("ESP ID").unwrap(), 7);
+        assert_eq!(reader.hex::<u16>("Blend").unwrap(), 0x1F);
+        assert_eq!(reader.dec::<u32>("Time").unwrap(), 0);
+        assert_eq!(reader.dec_optional::<u32>("Delay").unwrap(), None);
+        reader.finish(&mut warnings);
+        assert_eq!(warnings, ["test.txt2:4: Effect 1: missing Time, using default values"]);
+    }
+
+    #[test]
+    fn unknown_and_repeated_fields() {
+        let file = parse("Entry 0:\nID: 0x1\nID: 0x2\nColor: red\n").unwrap();
+        let mut warnings = Vec::new();
+        let entries = file.titled("Entry", &mut warnings).unwrap();
+        let mut reader = BlockReader::new(&file.path, entries[0]);
+        assert_eq!(reader.hex::<u16>("ID").unwrap(), 1);
+        reader.finish(&mut warnings);
+        assert_eq!(
+            warnings,
+            [
+                "test.txt2:3: unknown or repeated field \"ID\", ignored",
+                "test.txt2:4: unknown or repeated field \"Color\", ignored"
+            ]
+        );
+    }
+
+    #[test]
+    fn bad_values() {
+        let file = parse("Entry 0:\nID: 12\nBytes: 00 1F A0\nWrong: 0G\n").unwrap();
+        let reader = BlockReader::new(&file.path, &file.blocks[1]);
+        let [id, bytes, wrong] = [0, 1, 2].map(|index| &file.blocks[1].fields[index]);
+        assert!(matches!(reader.parse_hex::<u16>(id), Err(EffError::Parse { line: 2, .. })));
+        assert_eq!(reader.parse_bytes(bytes).unwrap(), [0x00, 0x1F, 0xA0]);
+        assert!(reader.parse_bytes(wrong).is_err());
+        assert_eq!(u8::from_hex("0xFF"), Some(0xFF));
+        assert_eq!(u8::from_hex("0x100"), None);
+
+        assert!(matches!(parse("Entry 0:\nno colon\n"), Err(EffError::Parse { line: 2, .. })));
+    }
+
+    #[test]
+    fn duplicate_and_missing_titles() {
+        let mut warnings = Vec::new();
+        let file = parse("Entry 0:\nEntry 0:\n").unwrap();
+        assert!(matches!(file.titled("Entry", &mut warnings), Err(EffError::Duplicate { line: 2, .. })));
+
+        let file = parse("Entry 0:\nEntry 2:\n").unwrap();
+        match file.titled("Entry", &mut warnings) {
+            Err(EffError::MissingField { field, .. }) => assert_eq!(field, "Entry 1"),
+            other => panic!("expected a missing Entry 1, got {:?}", other.map(|blocks| blocks.len())),
+        }
+
+        let file = parse("Effect 0:\nEntry 0:\n").unwrap();
+        assert_eq!(file.titled("Entry", &mut warnings).unwrap().len(), 1);
+        assert_eq!(warnings, ["test.txt2:1: unexpected \"Effect 0\", ignored"]);
+    }
+}
@@ -0,0 +1,79 @@
+// arquivo pequeno, mas com todas as tabelas, usado pelos testes
+use super::{Curve, CurvePoint, EarLink, Eff, Effect, EffectGroup, EffectType, TableEntry, TextureMetadata};
+
+// unknowns diferentes de zero, para que um campo perdido no txt2 apareca nos testes
+fn entry(id: u16) -> TableEntry {
+    TableEntry {
+        id,
+        unknown_a: 0x100 + id,
+        unknown_b: 0xAB00_0000 | id as u32,
+    }
+}
+
+fn effect(esp_id: u8, texture_id: u8) -> Effect {
+    Effect {
+        esp_id,
+        texture_id,
+        time: 30,
+        position: (100.0, 50.0, -25.5),
+        random: (10.0, 0.0, 10.0),
+        speed: (0.0, 1.5, 0.0),
+        width: 64.0,
+        height: 64.0,
+        rgba: (255, 128, 64, 255),
+        eff_type: EffectType::ESP,
+        ..Effect::default()
+    }
+}
+
+fn group(effects: Vec<Effect>) -> EffectGroup {
+    EffectGroup {
+        unknown_x02: 1,
+        unknown_x0c: 1.0,
+        unknown_x25: [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10],
+        effects,
+        ..EffectGroup::default()
+    }
+}
+
+pub(crate) fn point(x: f32, y: f32, z: f32) -> CurvePoint {
+    CurvePoint {
+        point: (x, y, z),
+        handle_0: (x, y, z),
+        handle_1: (x, y, z),
+        unknown: 0.0,
+    }
+}
+
+pub(crate) fn eff() -> Eff {
+    Eff {
+        texture_ids: vec![entry(10), entry(11)],
+        core_ids: vec![entry(0)],
+        ear_links: vec![EarLink {
+            id: 0,
+            ear_link_id: 5,
+            unknown: 0x1234_5678,
+        }],
+        unknown_table: vec![entry(0)],
+        model_ids: vec![entry(3)],
+        table_5: Vec::new(),
+        tpls_metadata: vec![
+            TextureMetadata {
+                texture_height: 64,
+                texture_width: 64,
+                effect_height: 64,
+                effect_width: 64,
+                texture_count: 1,
+                unknown_1: 0,
+                unknown_2: 0,
+            };
+            2
+        ],
+        effects_0: vec![group(vec![effect(1, 0), effect(2, 1)])],
+        effects_1: vec![group(vec![effect(3, 1)])],
+        paths: vec![Curve {
+            points: vec![point(0.0, 0.0, 0.0), point(100.0, 0.0, 0.0)],
+        }],
+        table_10: Vec::new(),
+    }
+}
@@ -0,0 +1,1346 @@
+use super::manifest::Manifest;
+use super::reader::{titled, BlockReader, Field, FromHex, TextFile};
+use super::{
+    Blend, ControlFlags, ControlPathFlags, Curve, CurvePoint, EarLink, Eff, EffError, Effect,
+    EffectFlags, EffectGroup, EffectType, Result, SimulationType, TableEntry, TextureMetadata,
+};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+// grava o conteudo no arquivo, criando a pasta se necessario
+pub(super) fn write_file(path: &Path, content: &[u8]) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|error| EffError::io(parent, error))?;
+    }
+    std::fs::write(path, content).map_err(|error| EffError::io(path, error))
+}
+
+fn write_table_entry(file: &mut Vec<u8>, index: usize, entry: &TableEntry) -> Result<()> {
+    file.write_all(format!("Entry {}: 0x{:X}\n", index, entry.id).as_bytes())?;
+    file.write_all(format!("Entry {} Unknown A: 0x{:X}\n", index, entry.unknown_a).as_bytes())?;
+    file.write_all(format!("Entry {} Unknown B: 0x{:X}\n", index, entry.unknown_b).as_bytes())?;
+    Ok(())
+}
+
+fn write_texture_ids(eff: &Eff, path: &Path) -> Result<()> {
+    let mut file = Vec::<u8>::new();
+
+    file.write_all(format!("Entry Count: {}\n", eff.texture_ids.len()).as_bytes())?;
+    for (index, texture_id) in eff.texture_ids.iter().enumerate() {
+        write_table_entry(&mut file, index, texture_id)?;
+    }
+
+    write_file(&path.join("Table_0_TPL_Texture_IDs.txt2"), &file)
+}
+
+fn write_core_ids(eff: &Eff, path: &Path) -> Result<()> {
+    let mut file = Vec::<u8>::new();
+
+    file.write_all(format!("Entry Count: {}\n", eff.core_ids.len()).as_bytes())?;
+    for (index, texture_id) in eff.core_ids.iter().enumerate() {
+        write_table_entry(&mut file, index, texture_id)?;
+    }
+
+    write_file(&path.join("Table_1_Effect_0_Indexes.txt2"), &file)
+}
+
+fn write_ear_links(eff: &Eff, path: &Path) -> Result<()> {
+    let mut file = Vec::<u8>::new();
+
+    file.write_all(format!("Entry Count: {}\n\n", eff.ear_links.len()).as_bytes())?;
+    for (index, link) in eff.ear_links.iter().enumerate() {
+        file.write_all(format!("Entry {} Effect Group: 0x{:X}\n", index, link.id).as_bytes())?;
+        file.write_all(
+            format!("Entry {} EAR Link ID: 0x{:X}\n", index, link.ear_link_id).as_bytes(),
+        )?;
+        file.write_all(format!("Entry {} Unknown: 0x{:X}\n\n", index, link.unknown).as_bytes())?;
+    }
+
+    write_file(&path.join("Table_2_EAR_Links.txt2"), &file)
+}
+
+fn write_unknown_table(eff: &Eff, path: &Path) -> Result<()> {
+    let mut file = Vec::<u8>::new();
+
+    file.write_all(format!("Entry Count: {}\n", eff.unknown_table.len()).as_bytes())?;
+    for (index, unknown) in eff.unknown_table.iter().enumerate() {
+        write_table_entry(&mut file, index, unknown)?;
+    }
+
+    write_file(&path.join("Table_3_Effect_Path_IDs.txt2"), &file)
+}
+
+fn write_model_ids(eff: &Eff, path: &Path) -> Result<()> {
+    let mut file = Vec::<u8>::new();
+
+    file.write_all(format!("Entry Count: {}\n", eff.model_ids.len()).as_bytes())?;
+    for (index, model_id) in eff.model_ids.iter().enumerate() {
+        write_table_entry(&mut file, index, model_id)?;
+    }
+
+    write_file(&path.join("Table_4_BIN_Model_IDs.txt2"), &file)
+}
+
+fn write_tpl_metadata_txt(eff: &Eff, path: &Path) -> Result<()> {
+    let mut file = Vec::<u8>::new();
+
+    file.write_all(format!("Texture Count: {}\n\n", eff.tpls_metadata.len()).as_bytes())?;
+    for (index, texture_data) in eff.tpls_metadata.iter().enumerate() {
+        file.write_all(format!("Entry {}:\n", index).as_bytes())?;
+        file.write_all(format!("Height: {}\n", texture_data.texture_height).as_bytes())?;
+        file.write_all(format!("Width: {}\n", texture_data.texture_width).as_bytes())?;
+        file.write_all(format!("Effect Height: {}\n", texture_data.effect_height).as_bytes())?;
+        file.write_all(format!("Effect Width: {}\n", texture_data.effect_width).as_bytes())?;
+        file.write_all(
+            format!("Effect Texture Count: {}\n", texture_data.texture_count).as_bytes(),
+        )?;
+        file.write_all(format!("Offset[10]: {}\n", texture_data.unknown_1).as_bytes())?;
+        file.write_all(format!("Offset[11]: {}\n\n", texture_data.unknown_2).as_bytes())?;
+    }
+
+    write_file(&path.join("Table_6_TextureData.txt2"), &file)
+}
+
+// nomes dos campos nos arquivos txt2, pelo nome do campo no JSON; tuplas tem um nome por valor.
+// O diff usa estes nomes, para que as diferencas apontem para as linhas dos txt2
+pub(super) const TABLE_ENTRY_LABELS: &[(&str, &[&str])] = &[
+    ("id", &["ID"]),
+    ("unknown_a", &["Unknown A"]),
+    ("unknown_b", &["Unknown B"]),
+];
+
+pub(super) const EAR_LINK_LABELS: &[(&str, &[&str])] = &[
+    ("id", &["Effect Group"]),
+    ("ear_link_id", &["EAR Link ID"]),
+    ("unknown", &["Unknown"]),
+];
+
+pub(super) const TEXTURE_METADATA_LABELS: &[(&str, &[&str])] = &[
+    ("texture_height", &["Height"]),
+    ("texture_width", &["Width"]),
+    ("effect_height", &["Effect Height"]),
+    ("effect_width", &["Effect Width"]),
+    ("texture_count", &["Effect Texture Count"]),
+    ("unknown_1", &["Offset[10]"]),
+    ("unknown_2", &["Offset[11]"]),
+];
+
+pub(super) const EFFECT_GROUP_LABELS: &[(&str, &[&str])] = &[
+    ("unknown_x02", &["Offset[X02]"]),
+    ("unknown_x04", &["Offset[X04]"]),
+    ("unknown_x06", &["Offset[X06]"]),
+    ("unknown_x08", &["Offset[X08]"]),
+    ("unknown_x0a", &["Offset[X0A]"]),
+    ("unknown_x0b", &["Offset[X0B]"]),
+    ("unknown_x0c", &["Offset[X0C]"]),
+    ("unknown_x10", &["Offset[X10]"]),
+    ("unknown_x14", &["Offset[X14]"]),
+    ("unknown_x18", &["Offset[X18]"]),
+    ("unknown_x1c", &["Offset[X1C]"]),
+    ("unknown_x20", &["Offset[X20]"]),
+    ("unknown_x24", &["Offset[X24]"]),
+    ("unknown_x25", &["Offset[X25]"]),
+];
+
+pub(super) const EFFECT_LABELS: &[(&str, &[&str])] = &[
+    ("state_id", &["State ID"]),
+    ("esp_id", &["ESP ID"]),
+    ("texture_id", &["Texture ID"]),
+    ("unknown_x03", &["Unknown X03"]),
+    ("time", &["Delay"]),
+    ("parent", &["Parent"]),
+    ("part", &["Parent Part"]),
+    ("flags", &["Flags"]),
+    ("position", &["Position X", "Position Y", "Position Z"]),
+    ("random", &["Random Position X", "Random Position Y", "Random Position Z"]),
+    ("speed", &["Speed X", "Speed Y", "Speed Z"]),
+    ("delta_speed", &["Delta Speed"]),
+    ("random_speed", &["Random Speed X", "Random Speed Y", "Random Speed Z"]),
+    ("acceleration", &["Acceleration X", "Acceleration Y", "Acceleration Z"]),
+    ("random_acceleration", &["Random Acceleration X", "Random Acceleration Y", "Random Acceleration Z"]),
+    ("rotate", &["Rotation X", "Rotation Y", "Rotation Z"]),
+    ("random_rotate", &["Random Rotation X", "Random Rotation Y", "Random Rotation Z"]),
+    ("rotate_acceleration", &["Rotation Acceleration X", "Rotation Acceleration Y", "Rotation Acceleration Z"]),
+    ("random_rotate_acceleration", &["Random Rotation Acceleration X", "Random Rotation Acceleration Y", "Random Rotation Acceleration Z"]),
+    ("width", &["Width"]),
+    ("height", &["Height"]),
+    ("random_size", &["Random Size"]),
+    ("grow", &["Grow"]),
+    ("delta_grow", &["Delta Grow"]),
+    ("rgba", &["R", "G", "B", "A"]),
+    ("delta_color", &["Delta R", "Delta G", "Delta B", "Delta A"]),
+    ("delta_color_attack", &["Delta Color Max Frame"]),
+    ("delta_color_start_frame", &["Delta Color Start Frame"]),
+    ("unknown_xb4", &["Unknown XB4"]),
+    ("delta_size_start_frame", &["Delta Size Start Frame"]),
+    ("life_time", &["Lifetime"]),
+    ("animation_speed", &["Animation Speed"]),
+    ("unknown_xbe", &["Unknown XBE"]),
+    ("release_time", &["Release Time"]),
+    ("blend", &["Blend"]),
+    ("simulation_type", &["Simulation Type"]),
+    ("simulation_power", &["Simulation Power"]),
+    ("mask_texture_id", &["Mask Texture ID"]),
+    ("value_in", &["Value In"]),
+    ("value_out", &["Value Out"]),
+    ("work_0", &["Work 0"]),
+    ("work_1", &["Work 1"]),
+    ("work_2", &["Work 2"]),
+    ("work_3", &["Work 3"]),
+    ("work_4", &["Work 4"]),
+    ("work_5", &["Work 5"]),
+    ("work_6", &["Work 6"]),
+    ("vector_0", &["Vector 0 X", "Vector 0 Y", "Vector 0 Z"]),
+    ("vector_1", &["Vector 1 X", "Vector 1 Y", "Vector 1 Z"]),
+    ("vector_2", &["Vector 2 X", "Vector 2 Y", "Vector 2 Z"]),
+    ("spline_0", &["Spline 0"]),
+    ("spline_1", &["Spline 1"]),
+    ("spline_2", &["Spline 2"]),
+    ("spline_3", &["Spline 3"]),
+    ("unknown_x100", &["Unknown X100"]),
+    ("path_own", &["Path Own"]),
+    ("path_number", &["Path Number"]),
+    ("path_start", &["Path Start"]),
+    ("path_random", &["Path Random"]),
+    ("eff_type", &["Effect Type"]),
+    ("control_id", &["Control ID"]),
+    ("control_flag", &["Control Flag"]),
+    ("control_interval", &["Control Interval"]),
+    ("control_number", &["Control Number"]),
+    ("control_rp", &["Control RP"]),
+    ("unknown_x10f", &["Unknown X10F"]),
+    ("control_life", &["Control Life"]),
+    ("unknown_x112", &["Unknown X112"]),
+    ("unknown_x114", &["Unknown X114"]),
+    ("unknown_x116", &["Unknown X116"]),
+    ("control_path_scale", &["Control Path Scale X", "Control Path Scale Y", "Control Path Scale Z"]),
+    ("control_path_delta_size", &["Control Path Delta Size"]),
+    ("control_path_delta_speed", &["Control Path Delta Speed"]),
+    ("control_path_delta_alpha", &["Control Path Delta Alpha"]),
+    ("control_path_delta_interval", &["Control Path Delta Interval"]),
+    ("control_path_random_interval", &["Control Path Random Interval"]),
+    ("control_path_rotation", &["Control Path Rotation X", "Control Path Rotation Y"]),
+    ("control_path_flag", &["Control Path Flag"]),
+];
+
+pub(super) const CURVE_POINT_LABELS: &[(&str, &[&str])] = &[
+    ("point", &["Position X", "Position Y", "Position Z"]),
+    ("handle_0", &["Handle 0 X", "Handle 0 Y", "Handle 0 Z"]),
+    ("handle_1", &["Handle 1 X", "Handle 1 Y", "Handle 1 Z"]),
+    ("unknown", &["Unknown"]),
+];
+
+/// Fields of an effect with the names and value format of the "Effect Group N Data.txt2"
+/// files, in file order. The CSV export uses the same list.
+pub(super) fn effect_fields(effect: &Effect) -> Vec<(&'static str, String)> {
+    vec![
+        ("State ID", format!("{}", effect.state_id)),
+        ("ESP ID", format!("0x{:X}", effect.esp_id)),
+        ("Texture ID", format!("0x{:X}", effect.texture_id)),
+        ("Unknown X03", format!("0x{:X}", effect.unknown_x03)),
+        ("Delay", format!("{}", effect.time)),
+        ("Parent", format!("0x{:X}", effect.parent)),
+        ("Parent Part", format!("0x{:X}", effect.part)),
+        ("Flags", format!("{}", effect.flags)),
+        ("Position X", format!("{}", effect.position.0)),
+        ("Position Y", format!("{}", effect.position.1)),
+        ("Position Z", format!("{}", effect.position.2)),
+        ("Random Position X", format!("{}", effect.random.0)),
+        ("Random Position Y", format!("{}", effect.random.1)),
+        ("Random Position Z", format!("{}", effect.random.2)),
+        ("Speed X", format!("{}", effect.speed.0)),
+        ("Speed Y", format!("{}", effect.speed.1)),
+        ("Speed Z", format!("{}", effect.speed.2)),
+        ("Delta Speed", format!("{}", effect.delta_speed)),
+        ("Random Speed X", format!("{}", effect.random_speed.0)),
+        ("Random Speed Y", format!("{}", effect.random_speed.1)),
+        ("Random Speed Z", format!("{}", effect.random_speed.2)),
+        ("Acceleration X", format!("{}", effect.acceleration.0)),
+        ("Acceleration Y", format!("{}", effect.acceleration.1)),
+        ("Acceleration Z", format!("{}", effect.acceleration.2)),
+        (
+            "Random Acceleration X",
+            format!("{}", effect.random_acceleration.0),
+        ),
+        (
+            "Random Acceleration Y",
+            format!("{}", effect.random_acceleration.1),
+        ),
+        (
+            "Random Acceleration Z",
+            format!("{}", effect.random_acceleration.2),
+        ),
+        ("Rotation X", format!("{}", effect.rotate.0)),
+        ("Rotation Y", format!("{}", effect.rotate.1)),
+        ("Rotation Z", format!("{}", effect.rotate.2)),
+        ("Random Rotation X", format!("{}", effect.random_rotate.0)),
+        ("Random Rotation Y", format!("{}", effect.random_rotate.1)),
+        ("Random Rotation Z", format!("{}", effect.random_rotate.2)),
+        (
+            "Rotation Acceleration X",
+            format!("{}", effect.rotate_acceleration.0),
+        ),
+        (
+            "Rotation Acceleration Y",
+            format!("{}", effect.rotate_acceleration.1),
+        ),
+        (
+            "Rotation Acceleration Z",
+            format!("{}", effect.rotate_acceleration.2),
+        ),
+        (
+            "Random Rotation Acceleration X",
+            format!("{}", effect.random_rotate_acceleration.0),
+        ),
+        (
+            "Random Rotation Acceleration Y",
+            format!("{}", effect.random_rotate_acceleration.1),
+        ),
+        (
+            "Random Rotation Acceleration Z",
+            format!("{}", effect.random_rotate_acceleration.2),
+        ),
+        ("Width", format!("{}", effect.width)),
+        ("Height", format!("{}", effect.height)),
+        ("Random Size", format!("{}", effect.random_size)),
+        ("Grow", format!("{}", effect.grow)),
+        ("Delta Grow", format!("{}", effect.delta_grow)),
+        ("R", format!("0x{:X}", effect.rgba.0)),
+        ("G", format!("0x{:X}", effect.rgba.1)),
+        ("B", format!("0x{:X}", effect.rgba.2)),
+        ("A", format!("0x{:X}", effect.rgba.3)),
+        ("Delta R", format!("{}", effect.delta_color.0)),
+        ("Delta G", format!("{}", effect.delta_color.1)),
+        ("Delta B", format!("{}", effect.delta_color.2)),
+        ("Delta A", format!("{}", effect.delta_color.3)),
+        (
+            "Delta Color Max Frame",
+            format!("{}", effect.delta_color_attack),
+        ),
+        (
+            "Delta Color Start Frame",
+            format!("{}", effect.delta_color_start_frame),
+        ),
+        ("Unknown XB4", format!("0x{:X}", effect.unknown_xb4)),
+        (
+            "Delta Size Start Frame",
+            format!("{}", effect.delta_size_start_frame),
+        ),
+        ("Lifetime", format!("{}", effect.life_time)),
+        ("Animation Speed", format!("{}", effect.animation_speed)),
+        ("Unknown XBE", format!("0x{:X}", effect.unknown_xbe)),
+        ("Release Time", format!("{}", effect.release_time)),
+        ("Blend", format!("{}", effect.blend)),
+        ("Simulation Type", format!("{}", effect.simulation_type)),
+        ("Simulation Power", format!("{}", effect.simulation_power)),
+        ("Mask Texture ID", format!("{}", effect.mask_texture_id)),
+        ("Value In", format!("{}", effect.value_in)),
+        ("Value Out", format!("{}", effect.value_out)),
+        ("Work 0", format!("{}", effect.work_0)),
+        ("Work 1", format!("{}", effect.work_1)),
+        ("Work 2", format!("{}", effect.work_2)),
+        ("Work 3", format!("{}", effect.work_3)),
+        ("Work 4", format!("{}", effect.work_4)),
+        ("Work 5", format!("{}", effect.work_5)),
+        ("Work 6", format!("{}", effect.work_6)),
+        ("Vector 0 X", format!("{}", effect.vector_0.0)),
+        ("Vector 0 Y", format!("{}", effect.vector_0.1)),
+        ("Vector 0 Z", format!("{}", effect.vector_0.2)),
+        ("Vector 1 X", format!("{}", effect.vector_1.0)),
+        ("Vector 1 Y", format!("{}", effect.vector_1.1)),
+        ("Vector 1 Z", format!("{}", effect.vector_1.2)),
+        ("Vector 2 X", format!("{}", effect.vector_2.0)),
+        ("Vector 2 Y", format!("{}", effect.vector_2.1)),
+        ("Vector 2 Z", format!("{}", effect.vector_2.2)),
+        ("Spline 0", format!("{}", effect.spline_0)),
+        ("Spline 1", format!("{}", effect.spline_1)),
+        ("Spline 2", format!("{}", effect.spline_2)),
+        ("Spline 3", format!("{}", effect.spline_3)),
+        ("Unknown X100", format!("0x{:X}", effect.unknown_x100)),
+        ("Path Own", format!("{}", effect.path_own)),
+        ("Path Number", format!("{}", effect.path_number)),
+        ("Path Start", format!("{}", effect.path_start)),
+        ("Path Random", format!("{}", effect.path_random)),
+        ("Effect Type", format!("{}", effect.eff_type)),
+        ("Control ID", format!("{}", effect.control_id)),
+        ("Control Flag", format!("{}", effect.control_flag)),
+        ("Control Interval", format!("{}", effect.control_interval)),
+        ("Control Number", format!("{}", effect.control_number)),
+        ("Control RP", format!("{}", effect.control_rp)),
+        ("Unknown X10F", format!("0x{:X}", effect.unknown_x10f)),
+        ("Control Life", format!("{}", effect.control_life)),
+        ("Unknown X112", format!("0x{:X}", effect.unknown_x112)),
+        ("Unknown X114", format!("0x{:X}", effect.unknown_x114)),
+        ("Unknown X116", format!("0x{:X}", effect.unknown_x116)),
+        (
+            "Control Path Scale X",
+            format!("{}", effect.control_path_scale.0),
+        ),
+        (
+            "Control Path Scale Y",
+            format!("{}", effect.control_path_scale.1),
+        ),
+        (
+            "Control Path Scale Z",
+            format!("{}", effect.control_path_scale.2),
+        ),
+        (
+            "Control Path Delta Size",
+            format!("{}", effect.control_path_delta_size),
+        ),
+        (
+            "Control Path Delta Speed",
+            format!("{}", effect.control_path_delta_speed),
+        ),
+        (
+            "Control Path Delta Alpha",
+            format!("{}", effect.control_path_delta_alpha),
+        ),
+        (
+            "Control Path Delta Interval",
+            format!("{}", effect.control_path_delta_interval),
+        ),
+        (
+            "Control Path Random Interval",
+            format!("{}", effect.control_path_random_interval),
+        ),
+        (
+            "Control Path Rotation X",
+            format!("{}", effect.control_path_rotation.0),
+        ),
+        (
+            "Control Path Rotation Y",
+            format!("{}", effect.control_path_rotation.1),
+        ),
+        ("Control Path Flag", format!("{}", effect.control_path_flag)),
+    ]
+}
+
+fn write_effect_to_txt(effect_group: &EffectGroup, path: &Path) -> Result<()> {
+    let mut file = Vec::<u8>::new(); //"Data.txt"
+
+    file.write_all(format!("Effect Count: {}\n", effect_group.effects.len()).as_bytes())?;
+    file.write_all(format!("Offset[X02]: 0x{:X}\n", effect_group.unknown_x02).as_bytes())?;
+    file.write_all(format!("Offset[X04]: 0x{:X}\n", effect_group.unknown_x04).as_bytes())?;
+    file.write_all(format!("Offset[X06]: 0x{:X}\n", effect_group.unknown_x06).as_bytes())?;
+    file.write_all(format!("Offset[X08]: 0x{:X}\n", effect_group.unknown_x08).as_bytes())?;
+    file.write_all(format!("Offset[X0A]: 0x{:X}\n", effect_group.unknown_x0a).as_bytes())?;
+    file.write_all(format!("Offset[X0B]: 0x{:X}\n", effect_group.unknown_x0b).as_bytes())?;
+    file.write_all(format!("Offset[X0C]: {}\n", effect_group.unknown_x0c).as_bytes())?;
+    file.write_all(format!("Offset[X10]: {}\n", effect_group.unknown_x10).as_bytes())?;
+    file.write_all(format!("Offset[X14]: {}\n", effect_group.unknown_x14).as_bytes())?;
+    file.write_all(format!("Offset[X18]: {}\n", effect_group.unknown_x18).as_bytes())?;
+    file.write_all(format!("Offset[X1C]: {}\n", effect_group.unknown_x1c).as_bytes())?;
+    file.write_all(format!("Offset[X20]: {}\n", effect_group.unknown_x20).as_bytes())?;
+    file.write_all(format!("Offset[X24]: 0x{:X}\n", effect_group.unknown_x24).as_bytes())?;
+    let unknown_x25: Vec<String> = effect_group
+        .unknown_x25
+        .iter()
+        .map(|byte| format!("{:02X}", byte))
+        .collect();
+    file.write_all(format!("Offset[X25]: {}\n", unknown_x25.join(" ")).as_bytes())?;
+    file.write_all(b"\n\n")?;
+
+    for (index, effect) in effect_group.effects.iter().enumerate() {
+        file.write_all(format!("Effect {}\n", index).as_bytes())?;
+        for (key, value) in effect_fields(effect) {
+            file.write_all(format!("{}: {}\n", key, value).as_bytes())?;
+        }
+        file.write_all(b"\n")?;
+    }
+
+    write_file(path, &file)
+}
+
+fn write_effect_to_obj(
+    effect_group: &EffectGroup,
+    group_number: usize,
+    table_number: usize,
+    path: &Path,
+) -> Result<()> {
+    let mut file = Vec::<u8>::new(); //"Model.obj"
+
+    let mut face_index = 1;
+
+    file.write_all(b"#For reference only, scale 1/100, Y is the height\n\n")?;
+    file.write_all(b"vn 0.0 0.0 -1.0\n")?;
+
+    for (index, effect) in effect_group.effects.iter().enumerate() {
+        file.write_all(
+            format!(
+                "v {} {} {}\n",
+                ((effect.position.0) / 100.0),
+                ((effect.position.1) / 100.0),
+                ((effect.position.2) / 100.0)
+            )
+            .as_bytes(),
+        )?;
+        file.write_all(
+            format!(
+                "v {} {} {}\n",
+                ((effect.position.0) / 100.0),
+                ((effect.position.1) / 100.0) + 10.0,
+                ((effect.position.2) / 100.0) + 10.0
+            )
+            .as_bytes(),
+        )?;
+        file.write_all(
+            format!(
+                "v {} {} {}\n",
+                ((effect.position.0) / 100.0),
+                ((effect.position.1) / 100.0) + 10.0,
+                ((effect.position.2) / 100.0) - 10.0
+            )
+            .as_bytes(),
+        )?;
+
+        file.write_all(
+            format!(
+                "g Table_{}_Group_{}_EffectIndex_{}_EspID_0x{:X}_TextureID_0x{:X}\n",
+                table_number, group_number, index, effect.esp_id, effect.texture_id
+            )
+            .as_bytes(),
+        )?;
+        file.write_all(
+            format!(
+                "f {}//1 {}//1 {}//1\n\n",
+                face_index,
+                face_index + 1,
+                face_index + 2
+            )
+            .as_bytes(),
+        )?;
+        face_index += 3;
+    }
+
+    write_file(path, &file)
+}
+
+fn write_effects_to_txt(
+    effect_group: &[EffectGroup],
+    table_number: usize,
+    path: &Path,
+) -> Result<()> {
+    std::fs::create_dir_all(path).map_err(|error| EffError::io(path, error))?;
+
+    for (index, effect) in effect_group.iter().enumerate() {
+        write_effect_to_txt(
+            effect,
+            &path.join(format!("Effect Group {} Data.txt2", index)),
+        )?;
+        write_effect_to_obj(
+            effect,
+            index,
+            table_number,
+            &path.join(format!("Effect Group {} Model.obj", index)),
+        )?;
+    }
+
+    Ok(())
+}
+
+fn write_curves_out(eff: &Eff, path: &Path) -> Result<()> {
+    let mut file = Vec::<u8>::new();
+
+    file.write_all(format!("Path Count: {}\n", eff.paths.len()).as_bytes())?;
+    for (index, path) in eff.paths.iter().enumerate() {
+        file.write_all(format!("Entry {}:\n", index).as_bytes())?;
+        file.write_all(format!("\tPoint Count: {}\n", path.points.len()).as_bytes())?;
+        for (point_index, point) in path.points.iter().enumerate() {
+            file.write_all(format!("\tPoint {}: \n", point_index).as_bytes())?;
+            file.write_all(format!("\t\tPosition X: {}\n", point.point.0).as_bytes())?;
+            file.write_all(format!("\t\tPosition Y: {}\n", point.point.1).as_bytes())?;
+            file.write_all(format!("\t\tPosition Z: {}\n", point.point.2).as_bytes())?;
+            file.write_all(format!("\t\tHandle 0 X: {}\n", point.handle_0.0).as_bytes())?;
+            file.write_all(format!("\t\tHandle 0 Y: {}\n", point.handle_0.1).as_bytes())?;
+            file.write_all(format!("\t\tHandle 0 Z: {}\n", point.handle_0.2).as_bytes())?;
+            file.write_all(format!("\t\tHandle 1 X: {}\n", point.handle_1.0).as_bytes())?;
+            file.write_all(format!("\t\tHandle 1 Y: {}\n", point.handle_1.1).as_bytes())?;
+            file.write_all(format!("\t\tHandle 1 Z: {}\n", point.handle_1.2).as_bytes())?;
+            file.write_all(format!("\t\tUnknown: {}\n", point.unknown).as_bytes())?;
+        }
+    }
+
+    write_file(&path.join("Table_9_Paths.txt2"), &file)
+}
+
+// Table 5 and Table 10 are kept as raw bytes, 16 per line
+fn write_raw_table(bytes: &[u8], path: &Path) -> Result<()> {
+    let mut file = Vec::<u8>::new();
+
+    file.write_all(
+        b"# Raw bytes, research needed. Kept in the byte order of the original file.\n",
+    )?;
+    file.write_all(format!("Byte Count: {}\n", bytes.len()).as_bytes())?;
+    for (index, line) in bytes.chunks(16).enumerate() {
+        let hex: Vec<String> = line.iter().map(|byte| format!("{:02X}", byte)).collect();
+        file.write_all(format!("0x{:04X}: {}\n", index * 16, hex.join(" ")).as_bytes())?;
+    }
+
+    write_file(path, &file)
+}
+
+// cria os arqivos de txt, na parte da extracao
+// o manifesto é gravado no .EFFBLOBTXT, ao lado da pasta
+pub fn write_to_text(eff: &Eff, path: &Path, manifest: &Manifest) -> Result<()> {
+    write_texture_ids(eff, path.join("Tables").as_path())?;
+    write_core_ids(eff, path.join("Tables").as_path())?;
+    write_ear_links(eff, path.join("Tables").as_path())?;
+    write_unknown_table(eff, path.join("Tables").as_path())?;
+    write_model_ids(eff, path.join("Tables").as_path())?;
+    write_raw_table(&eff.table_5, path.join("Tables/Table_5_Raw.txt2").as_path())?;
+    write_raw_table(
+        &eff.table_10,
+        path.join("Tables/Table_10_Raw.txt2").as_path(),
+    )?;
+    write_curves_out(eff, path.join("Tables").as_path())?;
+    write_tpl_metadata_txt(eff, path.join("Tables").as_path())?;
+    write_effects_to_txt(&eff.effects_0, 7, path.join("Effect 0").as_path())?;
+    write_effects_to_txt(&eff.effects_1, 8, path.join("Effect 1").as_path())?;
+    manifest.write(path)?;
+    Ok(())
+}
+
+// parte do repack le os arquivos .txt
+// os campos sao encontrados pelo nome; campos ausentes usam o valor padrao e geram um aviso
+
+// "Entry 3 Unknown A" -> (3, "Unknown A"), "Entry 3" -> (3, "")
+fn entry_key(key: &str) -> Option<(usize, &str)> {
+    let rest = key.strip_prefix("Entry ")?;
+    let (index, name) = rest.split_once(' ').unwrap_or((rest, ""));
+    Some((index.parse().ok()?, name))
+}
+
+// quantidade de entradas "Entry N ..." do bloco; numeracao com falha é erro
+fn entry_count(reader: &BlockReader, path: &Path) -> Result<usize> {
+    let mut indexes: Vec<(usize, usize)> = reader
+        .fields()
+        .iter()
+        .filter_map(|field| entry_key(&field.key).map(|(index, _)| (index, field.line)))
+        .collect();
+    indexes.sort();
+    indexes.dedup_by_key(|(index, _)| *index);
+
+    for (expected, (index, line)) in indexes.iter().enumerate() {
+        if *index != expected {
+            return Err(EffError::MissingField {
+                path: path.to_path_buf(),
+                line: *line,
+                field: format!("Entry {}", expected),
+            });
+        }
+    }
+    Ok(indexes.len())
+}
+
+// contador informativo: avisa quando não confere com a quantidade lida
+fn check_count(
+    reader: &mut BlockReader,
+    key: &str,
+    found: usize,
+    warnings: &mut Vec<String>,
+) -> Result<()> {
+    if let Some(count) = reader.dec_optional::<usize>(key)? {
+        if count != found {
+            warnings
+                .push(reader.warning(&format!("{} is {} but {} were found", key, count, found)));
+        }
+    }
+    Ok(())
+}
+
+// repack 1º funcao
+fn read_table(path: &Path, warnings: &mut Vec<String>) -> Result<Vec<TableEntry>> {
+    let file = TextFile::open(path)?;
+    let mut reader = BlockReader::new(&file.path, file.header());
+
+    let count = entry_count(&reader, path)?;
+    check_count(&mut reader, "Entry Count", count, warnings)?;
+
+    let mut result = Vec::<TableEntry>::with_capacity(count);
+    for index in 0..count {
+        let id = reader.hex(&format!("Entry {}", index))?;
+        // campos de arquivos extraidos por versoes anteriores podem nao existir, ficando 0
+        let unknown_a = reader.hex(&format!("Entry {} Unknown A", index))?;
+        let unknown_b = reader.hex(&format!("Entry {} Unknown B", index))?;
+        result.push(TableEntry {
+            id,
+            unknown_a,
+            unknown_b,
+        });
+    }
+
+    reader.finish(warnings);
+    Ok(result)
+}
+
+// repack 5º funcao
+fn read_ear_links(path: &Path, warnings: &mut Vec<String>) -> Result<Vec<EarLink>> {
+    let file = TextFile::open(path)?;
+    let mut reader = BlockReader::new(&file.path, file.header());
+
+    let count = entry_count(&reader, path)?;
+    check_count(&mut reader, "Entry Count", count, warnings)?;
+
+    let mut result = Vec::<EarLink>::with_capacity(count);
+    for index in 0..count {
+        let id = reader.hex(&format!("Entry {} Effect Group", index))?;
+        let ear_link_id = reader.hex(&format!("Entry {} EAR Link ID", index))?;
+        let unknown = reader.hex(&format!("Entry {} Unknown", index))?;
+
+        result.push(EarLink {
+            id,
+            ear_link_id,
+            unknown,
+        });
+    }
+
+    reader.finish(warnings);
+    Ok(result)
+}
+
+// repack Table 5 e Table 10, linhas "0x0010: 00 01 ..." em qualquer ordem
+fn read_raw_table(path: &Path, warnings: &mut Vec<String>) -> Result<Vec<u8>> {
+    let file = TextFile::open(path)?;
+    let mut reader = BlockReader::new(&file.path, file.header());
+
+    let mut lines = Vec::<(u32, &Field)>::new();
+    for field in reader.fields() {
+        if let Some(offset) = u32::from_hex(&field.key) {
+            lines.push((offset, field));
+        }
+    }
+    lines.sort_by_key(|(offset, _)| *offset);
+
+    let mut result = Vec::<u8>::new();
+    for (offset, field) in lines {
+        if offset as usize != result.len() {
+            return Err(EffError::MissingField {
+                path: path.to_path_buf(),
+                line: field.line,
+                field: format!("0x{:04X}", result.len()),
+            });
+        }
+        reader.optional(&field.key);
+        result.extend(reader.parse_bytes(field)?);
+    }
+
+    check_count(&mut reader, "Byte Count", result.len(), warnings)?;
+    reader.finish(warnings);
+    Ok(result)
+}
+
+// repack 6º funcao
+fn read_tpl_metadata(path: &Path, warnings: &mut Vec<String>) -> Result<Vec<TextureMetadata>> {
+    let file = TextFile::open(path)?;
+    let blocks = file.titled("Entry", warnings)?;
+
+    let mut header = BlockReader::new(&file.path, file.header());
+    check_count(&mut header, "Texture Count", blocks.len(), warnings)?;
+    header.finish(warnings);
+
+    let mut result = Vec::<TextureMetadata>::with_capacity(blocks.len());
+    for block in blocks {
+        let mut reader = BlockReader::new(&file.path, block);
+        result.push(TextureMetadata {
+            texture_height: reader.dec("Height")?,
+            texture_width: reader.dec("Width")?,
+            effect_height: reader.dec("Effect Height")?,
+            effect_width: reader.dec("Effect Width")?,
+            texture_count: reader.dec("Effect Texture Count")?,
+            unknown_1: reader.dec("Offset[10]")?,
+            unknown_2: reader.dec("Offset[11]")?,
+        });
+        reader.finish(warnings);
+    }
+
+    Ok(result)
+}
+
+// repack 8º funcao
+fn read_paths_from_file(path: &Path, warnings: &mut Vec<String>) -> Result<Vec<Curve>> {
+    let file = TextFile::open(path)?;
+    let blocks = file.titled("Entry", warnings)?;
+
+    let mut header = BlockReader::new(&file.path, file.header());
+    check_count(&mut header, "Path Count", blocks.len(), warnings)?;
+    header.finish(warnings);
+
+    let mut result = Vec::<Curve>::with_capacity(blocks.len());
+    for block in blocks {
+        let point_blocks = titled(&file.path, &block.children, "Point", warnings)?;
+        let mut reader = BlockReader::new(&file.path, block);
+        check_count(&mut reader, "Point Count", point_blocks.len(), warnings)?;
+        reader.finish(warnings);
+
+        let mut points = Vec::<CurvePoint>::with_capacity(point_blocks.len());
+        for point_block in point_blocks {
+            let mut reader = BlockReader::new(&file.path, point_block);
+            points.push(CurvePoint {
+                point: (
+                    reader.dec("Position X")?,
+                    reader.dec("Position Y")?,
+                    reader.dec("Position Z")?,
+                ),
+                handle_0: (
+                    reader.dec("Handle 0 X")?,
+                    reader.dec("Handle 0 Y")?,
+                    reader.dec("Handle 0 Z")?,
+                ),
+                handle_1: (
+                    reader.dec("Handle 1 X")?,
+                    reader.dec("Handle 1 Y")?,
+                    reader.dec("Handle 1 Z")?,
+                ),
+                unknown: reader.dec("Unknown")?,
+            });
+            reader.finish(warnings);
+        }
+
+        result.push(Curve { points });
+    }
+
+    Ok(result)
+}
+
+// subfuncao do repack 10
+pub(super) fn read_effect_from_file(reader: &mut BlockReader) -> Result<Effect> {
+    let state_id: u8 = reader.dec("State ID")?;
+    let esp_id: u8 = reader.hex("ESP ID")?;
+    let texture_id: u8 = reader.hex("Texture ID")?;
+    let unknown_x03: u8 = reader.hex("Unknown X03")?;
+    let time: u16 = reader.dec("Delay")?;
+    let parent: u8 = reader.hex("Parent")?;
+    let part: u8 = reader.hex("Parent Part")?;
+    let flags: EffectFlags = reader.dec("Flags")?;
+    let position_x: f32 = reader.dec("Position X")?;
+    let position_y: f32 = reader.dec("Position Y")?;
+    let position_z: f32 = reader.dec("Position Z")?;
+    let random_x: f32 = reader.dec("Random Position X")?;
+    let random_y: f32 = reader.dec("Random Position Y")?;
+    let random_z: f32 = reader.dec("Random Position Z")?;
+    let speed_x: f32 = reader.dec("Speed X")?;
+    let speed_y: f32 = reader.dec("Speed Y")?;
+    let speed_z: f32 = reader.dec("Speed Z")?;
+    let delta_speed: f32 = reader.dec("Delta Speed")?;
+    let random_speed_x: f32 = reader.dec("Random Speed X")?;
+    let random_speed_y: f32 = reader.dec("Random Speed Y")?;
+    let random_speed_z: f32 = reader.dec("Random Speed Z")?;
+    let acceleration_x: f32 = reader.dec("Acceleration X")?;
+    let acceleration_y: f32 = reader.dec("Acceleration Y")?;
+    let acceleration_z: f32 = reader.dec("Acceleration Z")?;
+    let random_acceleration_x: f32 = reader.dec("Random Acceleration X")?;
+    let random_acceleration_y: f32 = reader.dec("Random Acceleration Y")?;
+    let random_acceleration_z: f32 = reader.dec("Random Acceleration Z")?;
+    let rotation_x: f32 = reader.dec("Rotation X")?;
+    let rotation_y: f32 = reader.dec("Rotation Y")?;
+    let rotation_z: f32 = reader.dec("Rotation Z")?;
+    let random_rotation_x: f32 = reader.dec("Random Rotation X")?;
+    let random_rotation_y: f32 = reader.dec("Random Rotation Y")?;
+    let random_rotation_z: f32 = reader.dec("Random Rotation Z")?;
+    let rotation_acceleration_x: f32 = reader.dec("Rotation Acceleration X")?;
+    let rotation_acceleration_y: f32 = reader.dec("Rotation Acceleration Y")?;
+    let rotation_acceleration_z: f32 = reader.dec("Rotation Acceleration Z")?;
+    let random_rotation_acceleration_x: f32 = reader.dec("Random Rotation Acceleration X")?;
+    let random_rotation_acceleration_y: f32 = reader.dec("Random Rotation Acceleration Y")?;
+    let random_rotation_acceleration_z: f32 = reader.dec("Random Rotation Acceleration Z")?;
+    let width: f32 = reader.dec("Width")?;
+    let height: f32 = reader.dec("Height")?;
+    let random_size: f32 = reader.dec("Random Size")?;
+    let grow: f32 = reader.dec("Grow")?;
+    let delta_grow: f32 = reader.dec("Delta Grow")?;
+    let r: u8 = reader.hex("R")?;
+    let g: u8 = reader.hex("G")?;
+    let b: u8 = reader.hex("B")?;
+    let a: u8 = reader.hex("A")?;
+    let delta_r: f32 = reader.dec("Delta R")?;
+    let delta_g: f32 = reader.dec("Delta G")?;
+    let delta_b: f32 = reader.dec("Delta B")?;
+    let delta_a: f32 = reader.dec("Delta A")?;
+    let delta_color_attack: u16 = reader.dec("Delta Color Max Frame")?;
+    let delta_color_start_frame: u16 = reader.dec("Delta Color Start Frame")?;
+    let unknown_xb4: u16 = reader.hex("Unknown XB4")?;
+    let delta_size_start_frame: u16 = reader.dec("Delta Size Start Frame")?;
+    let life_time: u16 = reader.dec("Lifetime")?;
+    let animation_speed: u32 = reader.dec("Animation Speed")?;
+    let unknown_xbe: u16 = reader.hex("Unknown XBE")?;
+    let release_time: u8 = reader.dec("Release Time")?;
+    let blend: Blend = reader.dec("Blend")?;
+    let simulation_type: SimulationType = reader.dec("Simulation Type")?;
+    let simulation_power: u8 = reader.dec("Simulation Power")?;
+    let mask_texture_id: u8 = reader.dec("Mask Texture ID")?;
+    let value_in: u8 = reader.dec("Value In")?;
+    let value_out: u8 = reader.dec("Value Out")?;
+    let work_0: u8 = reader.dec("Work 0")?;
+    let work_1: u8 = reader.dec("Work 1")?;
+    let work_2: u8 = reader.dec("Work 2")?;
+    let work_3: u8 = reader.dec("Work 3")?;
+    let work_4: u32 = reader.dec("Work 4")?;
+    let work_5: u32 = reader.dec("Work 5")?;
+    let work_6: u32 = reader.dec("Work 6")?;
+    let vector_0_x: f32 = reader.dec("Vector 0 X")?;
+    let vector_0_y: f32 = reader.dec("Vector 0 Y")?;
+    let vector_0_z: f32 = reader.dec("Vector 0 Z")?;
+    let vector_1_x: f32 = reader.dec("Vector 1 X")?;
+    let vector_1_y: f32 = reader.dec("Vector 1 Y")?;
+    let vector_1_z: f32 = reader.dec("Vector 1 Z")?;
+    let vector_2_x: f32 = reader.dec("Vector 2 X")?;
+    let vector_2_y: f32 = reader.dec("Vector 2 Y")?;
+    let vector_2_z: f32 = reader.dec("Vector 2 Z")?;
+    let spline_0: u8 = reader.dec("Spline 0")?;
+    let spline_1: u8 = reader.dec("Spline 1")?;
+    let spline_2: u8 = reader.dec("Spline 2")?;
+    let spline_3: u8 = reader.dec("Spline 3")?;
+    let unknown_x100: u32 = reader.hex("Unknown X100")?;
+    let path_own: u8 = reader.dec("Path Own")?;
+    let path_number: u8 = reader.dec("Path Number")?;
+    let path_start: u8 = reader.dec("Path Start")?;
+    let path_random: u8 = reader.dec("Path Random")?;
+    let eff_type: EffectType = reader.dec("Effect Type")?;
+    let control_id: u8 = reader.dec("Control ID")?;
+    let control_flag: ControlFlags = reader.dec("Control Flag")?;
+    let control_interval: u8 = reader.dec("Control Interval")?;
+    let control_number: u8 = reader.dec("Control Number")?;
+    let control_rp: u8 = reader.dec("Control RP")?;
+    let unknown_x10f: u8 = reader.hex("Unknown X10F")?;
+    let control_life: u16 = reader.dec("Control Life")?;
+    let unknown_x112: u16 = reader.hex("Unknown X112")?;
+
+    let unknown_x114: u16 = reader.hex("Unknown X114")?;
+
+    let unknown_x116: u16 = reader.hex("Unknown X116")?;
+    let control_path_scale_x: f32 = reader.dec("Control Path Scale X")?;
+    let control_path_scale_y: f32 = reader.dec("Control Path Scale Y")?;
+    let control_path_scale_z: f32 = reader.dec("Control Path Scale Z")?;
+    let control_path_delta_size: u8 = reader.dec("Control Path Delta Size")?;
+    let control_path_delta_speed: u8 = reader.dec("Control Path Delta Speed")?;
+    let control_path_delta_alpha: u8 = reader.dec("Control Path Delta Alpha")?;
+    let control_path_delta_interval: u8 = reader.dec("Control Path Delta Interval")?;
+    let control_path_random_interval: u8 = reader.dec("Control Path Random Interval")?;
+    let control_path_rotation_x: u8 = reader.dec("Control Path Rotation X")?;
+    let control_path_rotation_y: u8 = reader.dec("Control Path Rotation Y")?;
+    let control_path_flag: ControlPathFlags = reader.dec("Control Path Flag")?;
+
+    Ok(Effect {
+        state_id,
+        esp_id,
+        texture_id,
+        unknown_x03,
+        time,
+        parent,
+        part,
+        flags,
+        position: (position_x, position_y, position_z),
+        random: (random_x, random_y, random_z),
+        speed: (speed_x, speed_y, speed_z),
+        delta_speed,
+        random_speed: (random_speed_x, random_speed_y, random_speed_z),
+        acceleration: (acceleration_x, acceleration_y, acceleration_z),
+        random_acceleration: (
+            random_acceleration_x,
+            random_acceleration_y,
+            random_acceleration_z,
+        ),
+        rotate: (rotation_x, rotation_y, rotation_z),
+        random_rotate: (random_rotation_x, random_rotation_y, random_rotation_z),
+        rotate_acceleration: (
+            rotation_acceleration_x,
+            rotation_acceleration_y,
+            rotation_acceleration_z,
+        ),
+        random_rotate_acceleration: (
+            random_rotation_acceleration_x,
+            random_rotation_acceleration_y,
+            random_rotation_acceleration_z,
+        ),
+        width,
+        height,
+        random_size,
+        grow,
+        delta_grow,
+        rgba: (r, g, b, a),
+        delta_color: (delta_r, delta_g, delta_b, delta_a),
+        delta_color_attack,
+        delta_color_start_frame,
+        unknown_xb4,
+        delta_size_start_frame,
+        life_time,
+        animation_speed,
+        unknown_xbe,
+        release_time,
+        blend,
+        simulation_type,
+        simulation_power,
+        mask_texture_id,
+        value_in,
+        value_out,
+        work_0,
+        work_1,
+        work_2,
+        work_3,
+        work_4,
+        work_5,
+        work_6,
+        vector_0: (vector_0_x, vector_0_y, vector_0_z),
+        vector_1: (vector_1_x, vector_1_y, vector_1_z),
+        vector_2: (vector_2_x, vector_2_y, vector_2_z),
+        spline_0,
+        spline_1,
+        spline_2,
+        spline_3,
+        unknown_x100,
+        path_own,
+        path_number,
+        path_start,
+        path_random,
+        eff_type,
+        control_id,
+        control_flag,
+        control_interval,
+        control_number,
+        control_rp,
+        unknown_x10f,
+        control_life,
+        unknown_x112,
+        unknown_x114,
+        unknown_x116,
+        control_path_scale: (
+            control_path_scale_x,
+            control_path_scale_y,
+            control_path_scale_z,
+        ),
+        control_path_delta_size,
+        control_path_delta_speed,
+        control_path_delta_alpha,
+        control_path_delta_interval,
+        control_path_random_interval,
+        control_path_rotation: (control_path_rotation_x, control_path_rotation_y),
+        control_path_flag,
+    })
+}
+
+/// The "Effect Group N Data.txt2" files of an effect folder, ordered by N.
+/// The numbering has to start at 0 without gaps; a missing folder has no groups.
+pub(crate) fn group_files(path: &Path) -> Result<Vec<PathBuf>> {
+    if !path.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let mut indexes = Vec::<usize>::new();
+    for entry in std::fs::read_dir(path).map_err(|error| EffError::io(path, error))? {
+        let entry = entry.map_err(|error| EffError::io(path, error))?;
+        let name = entry.file_name();
+        let index = name
+            .to_str()
+            .and_then(|name| name.strip_prefix("Effect Group "))
+            .and_then(|name| name.strip_suffix(" Data.txt2"))
+            .and_then(|index| index.parse::<usize>().ok());
+        if let Some(index) = index {
+            indexes.push(index);
+        }
+    }
+    indexes.sort();
+
+    for (expected, index) in indexes.iter().enumerate() {
+        if *index != expected {
+            return Err(EffError::MissingGroup {
+                path: path.to_path_buf(),
+                index: expected,
+            });
+        }
+    }
+
+    Ok(indexes
+        .into_iter()
+        .map(|index| path.join(format!("Effect Group {} Data.txt2", index)))
+        .collect())
+}
+
+//repack 10º e 11º
+fn read_effect_group_from_file(
+    path: &Path,
+    warnings: &mut Vec<String>,
+) -> Result<Vec<EffectGroup>> {
+    let mut result = Vec::<EffectGroup>::new();
+    for file in group_files(path)? {
+        let file = TextFile::open(&file)?;
+        let blocks = file.titled("Effect", warnings)?;
+        let mut reader = BlockReader::new(&file.path, file.header());
+
+        check_count(&mut reader, "Effect Count", blocks.len(), warnings)?;
+
+        let unknown_x02: u16 = reader.hex("Offset[X02]")?;
+        let unknown_x04: u16 = reader.hex("Offset[X04]")?;
+        let unknown_x06: u16 = reader.hex("Offset[X06]")?;
+        let unknown_x08: u16 = reader.hex("Offset[X08]")?;
+        let unknown_x0a: u8 = reader.hex("Offset[X0A]")?;
+        let unknown_x0b: u8 = reader.hex("Offset[X0B]")?;
+        let unknown_x0c: f32 = reader.dec("Offset[X0C]")?;
+        let unknown_x10: f32 = reader.dec("Offset[X10]")?;
+        let unknown_x14: f32 = reader.dec("Offset[X14]")?;
+        let unknown_x18: f32 = reader.dec("Offset[X18]")?;
+        let unknown_x1c: f32 = reader.dec("Offset[X1C]")?;
+        let unknown_x20: f32 = reader.dec("Offset[X20]")?;
+        let unknown_x24: u8 = reader.hex("Offset[X24]")?;
+        // arquivos extraidos por versoes anteriores nao possuem, ficando 0
+        let mut unknown_x25 = [0u8; 0xB];
+        if let Some(field) = reader.optional("Offset[X25]") {
+            unknown_x25 = reader
+                .parse_bytes(field)?
+                .try_into()
+                .map_err(|_| reader.parse_error(field))?;
+        }
+        reader.finish(warnings);
+
+        let mut effects = Vec::<Effect>::with_capacity(blocks.len());
+        for block in blocks {
+            let mut reader = BlockReader::new(&file.path, block);
+            effects.push(read_effect_from_file(&mut reader)?);
+            reader.finish(warnings);
+        }
+
+        result.push(EffectGroup {
+            unknown_x02,
+            unknown_x04,
+            unknown_x06,
+            unknown_x08,
+            unknown_x0a,
+            unknown_x0b,
+            unknown_x0c,
+            unknown_x10,
+            unknown_x14,
+            unknown_x18,
+            unknown_x1c,
+            unknown_x20,
+            unknown_x24,
+            unknown_x25,
+            effects,
+        });
+    }
+    Ok(result)
+}
+
+// inicio do repack
+pub fn read_from_text(path: &Path) -> Result<Eff> {
+    read_from_text_with_warnings(path).map(|(eff, _warnings)| eff)
+}
+
+/// Same as [`read_from_text`], also returning the warnings about missing or unknown
+/// fields found in the txt2 files.
+pub fn read_from_text_with_warnings(path: &Path) -> Result<(Eff, Vec<String>)> {
+    let mut warnings = Vec::<String>::new();
+    //variaveis
+    let mut texture_ids = Vec::<TableEntry>::new();
+    let mut core_ids = Vec::<TableEntry>::new();
+    let mut model_ids = Vec::<TableEntry>::new();
+    let mut unknown_table = Vec::<TableEntry>::new();
+    let mut ear_links = Vec::<EarLink>::new();
+    let mut table_5 = Vec::<u8>::new();
+    let mut table_10 = Vec::<u8>::new();
+    let mut tpls_metadata = Vec::<TextureMetadata>::new();
+    let mut paths = Vec::<Curve>::new();
+
+    //checagem
+    //1
+    if path
+        .join("Tables/Table_0_TPL_Texture_IDs.txt2")
+        .as_path()
+        .is_file()
+    {
+        texture_ids = read_table(
+            path.join("Tables/Table_0_TPL_Texture_IDs.txt2").as_path(),
+            &mut warnings,
+        )?;
+    }
+
+    //2
+    if path
+        .join("Tables/Table_1_Effect_0_Indexes.txt2")
+        .as_path()
+        .is_file()
+    {
+        core_ids = read_table(
+            path.join("Tables/Table_1_Effect_0_Indexes.txt2").as_path(),
+            &mut warnings,
+        )?;
+    }
+
+    //3
+    if path
+        .join("Tables/Table_4_BIN_Model_IDs.txt2")
+        .as_path()
+        .is_file()
+    {
+        model_ids = read_table(
+            path.join("Tables/Table_4_BIN_Model_IDs.txt2").as_path(),
+            &mut warnings,
+        )?;
+    }
+
+    //4
+    if path
+        .join("Tables/Table_3_Effect_Path_IDs.txt2")
+        .as_path()
+        .is_file()
+    {
+        unknown_table = read_table(
+            path.join("Tables/Table_3_Effect_Path_IDs.txt2").as_path(),
+            &mut warnings,
+        )?;
+    }
+
+    //5
+    if path
+        .join("Tables/Table_2_EAR_Links.txt2")
+        .as_path()
+        .is_file()
+    {
+        ear_links = read_ear_links(
+            path.join("Tables/Table_2_EAR_Links.txt2").as_path(),
+            &mut warnings,
+        )?;
+    }
+
+    //Table 5 e Table 10, arquivos extraidos em versoes anteriores nao possuem
+    if path.join("Tables/Table_5_Raw.txt2").as_path().is_file() {
+        table_5 = read_raw_table(
+            path.join("Tables/Table_5_Raw.txt2").as_path(),
+            &mut warnings,
+        )?;
+    }
+
+    if path.join("Tables/Table_10_Raw.txt2").as_path().is_file() {
+        table_10 = read_raw_table(
+            path.join("Tables/Table_10_Raw.txt2").as_path(),
+            &mut warnings,
+        )?;
+    }
+
+    //6
+    if path
+        .join("Tables/Table_6_TextureData.txt2")
+        .as_path()
+        .is_file()
+    {
+        tpls_metadata = read_tpl_metadata(
+            path.join("Tables/Table_6_TextureData.txt2").as_path(),
+            &mut warnings,
+        )?;
+    }
+
+    //8
+    if path.join("Tables/Table_9_Paths.txt2").as_path().is_file() {
+        paths = read_paths_from_file(
+            path.join("Tables/Table_9_Paths.txt2").as_path(),
+            &mut warnings,
+        )?;
+    }
+
+    //10 e 11, os grupos sao os arquivos da pasta; cada grupo tem uma entrada na Table 1 ou Table 2
+    let effects_0 = read_effect_group_from_file(path.join("Effect 0").as_path(), &mut warnings)?;
+    if effects_0.len() != core_ids.len() {
+        return Err(EffError::GroupCountMismatch {
+            folder: "Effect 0".to_owned(),
+            groups: effects_0.len(),
+            table: "Tables/Table_1_Effect_0_Indexes.txt2".to_owned(),
+            entries: core_ids.len(),
+        });
+    }
+
+    let effects_1 = read_effect_group_from_file(path.join("Effect 1").as_path(), &mut warnings)?;
+    if effects_1.len() != ear_links.len() {
+        return Err(EffError::GroupCountMismatch {
+            folder: "Effect 1".to_owned(),
+            groups: effects_1.len(),
+            table: "Tables/Table_2_EAR_Links.txt2".to_owned(),
+            entries: ear_links.len(),
+        });
+    }
+
+    Ok((
+        Eff {
+            texture_ids,
+            core_ids,
+            ear_links,
+            unknown_table,
+            model_ids,
+            table_5,
+            tpls_metadata,
+            effects_0,
+            effects_1,
+            paths,
+            table_10,
+        },
+        warnings,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eff::sample;
+    use scalar_types::Endian;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("re4_effblob_text_{}_{}", name, std::process::id()))
+    }
+
+    #[test]
+    fn round_trip() {
+        let eff = sample::eff();
+        let dir = temp_dir("round_trip");
+        write_to_text(&eff, &dir, &Manifest::new(&eff, &Endian::Little(()), None)).unwrap();
+        let read = read_from_text_with_warnings(&dir);
+        let _ = std::fs::remove_dir_all(&dir);
+
+        let (read, warnings) = read.unwrap();
+        assert_eq!(warnings, Vec::<String>::new());
+        // os unknowns das tabelas sao diferentes de zero no exemplo
+        assert_eq!(read.texture_ids, eff.texture_ids);
+        assert_eq!(read.ear_links, eff.ear_links);
+        assert_eq!(
+            read.compile(&Endian::Big(())).unwrap(),
+            eff.compile(&Endian::Big(())).unwrap()
+        );
+    }
+
+    // tabelas extraidas antes da versao 2 do layout nao tem os campos Unknown
+    #[test]
+    fn old_tables_without_unknowns() {
+        let eff = sample::eff();
+        let dir = temp_dir("old_tables");
+        write_to_text(&eff, &dir, &Manifest::new(&eff, &Endian::Little(()), None)).unwrap();
+        let old = |name: &str| {
+            let path = dir.join("Tables").join(name);
+            let text = std::fs::read_to_string(&path).unwrap();
+            let lines: Vec<&str> = text
+                .lines()
+                .filter(|line| !line.contains("Unknown"))
+                .collect();
+            std::fs::write(&path, lines.join("\n")).unwrap();
+        };
+        old("Table_0_TPL_Texture_IDs.txt2");
+        old("Table_2_EAR_Links.txt2");
+        let read = read_from_text_with_warnings(&dir);
+        let _ = std::fs::remove_dir_all(&dir);
+
+        let (read, warnings) = read.unwrap();
+        let ids: Vec<_> = read
+            .texture_ids
+            .iter()
+            .map(|entry| (entry.id, entry.unknown_a, entry.unknown_b))
+            .collect();
+        assert_eq!(ids, [(10, 0, 0), (11, 0, 0)]);
+        assert_eq!(
+            (read.ear_links[0].ear_link_id, read.ear_links[0].unknown),
+            (5, 0)
+        );
+        assert_eq!(read.core_ids, eff.core_ids);
+
+        assert_eq!(warnings.len(), 2, "{:?}", warnings);
+        assert!(warnings[0].contains("Table_0_TPL_Texture_IDs.txt2"));
+        assert!(warnings[0].contains("missing Entry 0 Unknown A, Entry 0 Unknown B"));
+        assert!(warnings[1].contains("Table_2_EAR_Links.txt2"));
+        assert!(warnings[1].contains("missing Entry 0 Unknown, using default values"));
+    }
+}
@@ -0,0 +1,263 @@
+use super::{Blend, Eff, Effect, EffectGroup, EffectType, Platform, SECTION_NAMES};
+use std::fmt;
+
+/// `Error` = the tables of the file do not fit together (the groups of Effect 0/1 and the
+/// entries of Table 1/2), `Warning` = a value that looks wrong. References whose meaning is
+/// not confirmed yet, such as the texture and path indexes, are warnings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+/// A problem found by [`Eff::validate`], with where it is, e.g. "Effect 0, Group 2, Effect 5".
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Issue {
+    pub severity: Severity,
+    pub location: String,
+    pub message: String,
+}
+
+impl Issue {
+    pub fn is_error(&self) -> bool {
+        self.severity == Severity::Error
+    }
+}
+
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let severity = match self.severity {
+            Severity::Warning => "Warning",
+            Severity::Error => "Error",
+        };
+        write!(f, "{}: {}: {}", severity, self.location, self.message)
+    }
+}
+
+struct Issues(Vec<Issue>);
+
+impl Issues {
+    fn push(&mut self, severity: Severity, location: &str, message: String) {
+        self.0.push(Issue {
+            severity,
+            location: location.to_owned(),
+            message,
+        });
+    }
+
+    // indice de uma tabela; uma tabela vazia aceita o 0, o padrao de um campo que nao é usado
+    fn index(&mut self, location: &str, field: &str, value: usize, table: usize, count: usize) {
+        if count == 0 && value != 0 {
+            let message = format!("{} {} but {} is empty", field, value, SECTION_NAMES[table]);
+            self.push(Severity::Warning, location, message);
+        } else if count != 0 && value >= count {
+            let message = format!("{} {} is not in {}, it has {} entries", field, value, SECTION_NAMES[table], count);
+            self.push(Severity::Warning, location, message);
+        }
+    }
+
+    // duas tabelas que deveriam ter uma entrada para cada coisa
+    fn counts(&mut self, severity: Severity, table: usize, count: usize, other: &str, other_count: usize) {
+        if count != other_count {
+            let message = format!("{} entries, but {} {}", count, other_count, other);
+            self.push(severity, SECTION_NAMES[table], message);
+        }
+    }
+}
+
+impl Eff {
+    /// Checks the references between the tables: the texture, mask and path of each effect,
+    /// the entries of Table 1/2 against the groups of Effect 0/1, the groups of the EAR links
+    /// and the path IDs of Table 3 against Table 9. Also reports enum values with no known
+    /// meaning. An empty list means nothing was found.
+    pub fn validate(&self) -> Vec<Issue> {
+        let mut issues = Issues(Vec::new());
+
+        issues.counts(Severity::Warning, 0, self.texture_ids.len(), "entries in Table 6 (Texture Data)", self.tpls_metadata.len());
+        issues.counts(Severity::Error, 1, self.core_ids.len(), "groups in Effect 0", self.effects_0.len());
+        issues.counts(Severity::Error, 2, self.ear_links.len(), "groups in Effect 1", self.effects_1.len());
+        issues.counts(Severity::Warning, 3, self.unknown_table.len(), "paths in Table 9 (Paths)", self.paths.len());
+
+        for (index, link) in self.ear_links.iter().enumerate() {
+            let location = format!("{}, Entry {}", SECTION_NAMES[2], index);
+            issues.index(&location, "Effect Group", link.id as usize, 8, self.effects_1.len());
+        }
+        for (index, entry) in self.unknown_table.iter().enumerate() {
+            let location = format!("{}, Entry {}", SECTION_NAMES[3], index);
+            issues.index(&location, "ID", entry.id as usize, 9, self.paths.len());
+        }
+        for (index, curve) in self.paths.iter().enumerate() {
+            if curve.points.is_empty() {
+                issues.push(Severity::Warning, SECTION_NAMES[9], format!("path {} has no points", index));
+            }
+        }
+
+        for (table, groups) in [(7, &self.effects_0), (8, &self.effects_1)] {
+            self.validate_groups(&mut issues, SECTION_NAMES[table], groups);
+        }
+
+        issues.0
+    }
+
+    /// [`Eff::validate`] for a file of `platform`. The checks are the same on every platform
+    /// known so far.
+    pub fn validate_for(&self, _platform: Platform) -> Vec<Issue> {
+        self.validate()
+    }
+
+    fn validate_groups(&self, issues: &mut Issues, table: &str, groups: &[EffectGroup]) {
+        for (group_index, group) in groups.iter().enumerate() {
+            for (effect_index, effect) in group.effects.iter().enumerate() {
+                let location = format!("{}, Group {}, Effect {}", table, group_index, effect_index);
+                self.validate_effect(issues, &location, effect);
+            }
+        }
+    }
+
+    fn validate_effect(&self, issues: &mut Issues, location: &str, effect: &Effect) {
+        // um Control so dispara outros efeitos, nao desenha textura; a Table 6 tem uma
+        // entrada para cada TPL da Table 0, conferida em validate
+        if effect.eff_type != EffectType::Control {
+            issues.index(location, "Texture ID", effect.texture_id as usize, 0, self.texture_ids.len());
+            issues.index(location, "Mask Texture ID", effect.mask_texture_id as usize, 0, self.texture_ids.len());
+        }
+        // Path Own 0 é tomado como um efeito sem path, entao o Path Number nao é usado
+        if effect.path_own != 0 {
+            issues.index(location, "Path Number", effect.path_number as usize, 9, self.paths.len());
+        }
+
+        if !effect.simulation_type.is_known() {
+            issues.push(Severity::Warning, location, format!("unknown Simulation Type {}", effect.simulation_type));
+        }
+        if !effect.eff_type.is_known() {
+            issues.push(Severity::Warning, location, format!("unknown Effect Type {}", effect.eff_type));
+        }
+        // os modos sem nome so geram aviso acima do limite do jogo
+        if !effect.blend.is_known() && effect.blend.value() > Blend::MAX {
+            let message = format!("Blend {} is above 0x{:X}, the game clamps it", effect.blend, Blend::MAX);
+            issues.push(Severity::Warning, location, message);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eff::{sample, Curve, SimulationType, TableEntry};
+
+    // (gravidade, local, mensagem) de cada problema
+    fn issues(eff: &Eff) -> Vec<(Severity, String, String)> {
+        eff.validate()
+            .into_iter()
+            .map(|issue| (issue.severity, issue.location, issue.message))
+            .collect()
+    }
+
+    fn one(severity: Severity, location: &str, message: &str) -> Vec<(Severity, String, String)> {
+        vec![(severity, location.to_owned(), message.to_owned())]
+    }
+
+    const EFFECT: &str = "Effect 0, Group 0, Effect 0";
+
+    #[test]
+    fn sample_is_valid() {
+        assert_eq!(issues(&sample::eff()), []);
+    }
+
+    #[test]
+    fn texture_tables() {
+        let mut eff = sample::eff();
+        eff.tpls_metadata.pop();
+        let message = "2 entries, but 1 entries in Table 6 (Texture Data)";
+        assert_eq!(issues(&eff), one(Severity::Warning, SECTION_NAMES[0], message));
+    }
+
+    #[test]
+    fn group_tables() {
+        let mut eff = sample::eff();
+        eff.core_ids.push(TableEntry::default());
+        assert_eq!(issues(&eff), one(Severity::Error, SECTION_NAMES[1], "2 entries, but 1 groups in Effect 0"));
+
+        let mut eff = sample::eff();
+        eff.ear_links.clear();
+        assert_eq!(issues(&eff), one(Severity::Error, SECTION_NAMES[2], "0 entries, but 1 groups in Effect 1"));
+    }
+
+    #[test]
+    fn ear_link_group() {
+        let mut eff = sample::eff();
+        eff.ear_links[0].id = 1;
+        let message = "Effect Group 1 is not in Effect 1, it has 1 entries";
+        assert_eq!(issues(&eff), one(Severity::Warning, "Table 2 (EAR Links), Entry 0", message));
+    }
+
+    #[test]
+    fn path_ids() {
+        let mut eff = sample::eff();
+        eff.unknown_table[0].id = 3;
+        let message = "ID 3 is not in Table 9 (Paths), it has 1 entries";
+        assert_eq!(issues(&eff), one(Severity::Warning, "Table 3 (Effect Path IDs), Entry 0", message));
+
+        let mut eff = sample::eff();
+        eff.paths.push(sample::eff().paths[0].clone());
+        let message = "1 entries, but 2 paths in Table 9 (Paths)";
+        assert_eq!(issues(&eff), one(Severity::Warning, SECTION_NAMES[3], message));
+    }
+
+    #[test]
+    fn empty_path() {
+        let mut eff = sample::eff();
+        eff.paths[0] = Curve::default();
+        assert_eq!(issues(&eff), one(Severity::Warning, SECTION_NAMES[9], "path 0 has no points"));
+    }
+
+    #[test]
+    fn texture_ids() {
+        let mut eff = sample::eff();
+        eff.effects_0[0].effects[0].texture_id = 2;
+        let message = "Texture ID 2 is not in Table 0 (TPL Texture IDs), it has 2 entries";
+        assert_eq!(issues(&eff), one(Severity::Warning, EFFECT, message));
+
+        eff.effects_0[0].effects[0].eff_type = EffectType::Control;
+        assert_eq!(issues(&eff), []);
+
+        let mut eff = sample::eff();
+        eff.texture_ids.clear();
+        eff.tpls_metadata.clear();
+        for group in &mut eff.effects_0 {
+            for effect in &mut group.effects {
+                effect.texture_id = 0;
+            }
+        }
+        eff.effects_1[0].effects[0].texture_id = 0;
+        eff.effects_1[0].effects[0].mask_texture_id = 1;
+        let message = "Mask Texture ID 1 but Table 0 (TPL Texture IDs) is empty";
+        assert_eq!(issues(&eff), one(Severity::Warning, "Effect 1, Group 0, Effect 0", message));
+    }
+
+    #[test]
+    fn path_number() {
+        let mut eff = sample::eff();
+        eff.effects_0[0].effects[0].path_number = 4;
+        assert_eq!(issues(&eff), []);
+
+        eff.effects_0[0].effects[0].path_own = 1;
+        let message = "Path Number 4 is not in Table 9 (Paths), it has 1 entries";
+        assert_eq!(issues(&eff), one(Severity::Warning, EFFECT, message));
+    }
+
+    #[test]
+    fn unknown_values() {
+        let mut eff = sample::eff();
+        eff.effects_0[0].effects[0].simulation_type = SimulationType::from_value(9);
+        assert_eq!(issues(&eff), one(Severity::Warning, EFFECT, "unknown Simulation Type 9"));
+
+        let mut eff = sample::eff();
+        eff.effects_0[0].effects[0].eff_type = EffectType::from_value(7);
+        assert_eq!(issues(&eff), one(Severity::Warning, EFFECT, "unknown Effect Type 7"));
+
+        let mut eff = sample::eff();
+        eff.effects_0[0].effects[0].blend = Blend::from_value(0x100);
+        assert_eq!(issues(&eff), one(Severity::Warning, EFFECT, "Blend 256 is above 0xFF, the game clamps it"));
+    }
+}
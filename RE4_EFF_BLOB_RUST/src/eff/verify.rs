@@ -0,0 +1,445 @@
+use super::manifest::Manifest;
+use super::text::{read_from_text, write_to_text};
+use super::{Eff, Result, CURVE_POINT_SIZE, EFFECT_SIZE, SECTION_NAMES, TABLE_ENTRY_SIZE};
+use scalar_types::Endian;
+use std::fmt;
+use std::io::Cursor;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+// tamanho do cabecalho do EffectGroup, antes do primeiro Effect
+const EFFECT_GROUP_HEADER_SIZE: u64 = 0x30;
+
+// (offset, nome) dos campos, os nomes sao os mesmos usados nos arquivos txt2
+const TABLE_ENTRY_FIELDS: [(u64, &str); 3] = [(0x0, "ID"), (0x2, "Unknown A"), (0x4, "Unknown B")];
+
+const EAR_LINK_FIELDS: [(u64, &str); 3] = [(0x0, "Effect Group"), (0x2, "EAR Link ID"), (0x4, "Unknown")];
+
+const TEXTURE_METADATA_FIELDS: [(u64, &str); 7] = [
+    (0x0, "Height"),
+    (0x2, "Width"),
+    (0x4, "Effect Height"),
+    (0x6, "Effect Width"),
+    (0x8, "Effect Texture Count"),
+    (0xA, "Offset[10]"),
+    (0xB, "Offset[11]"),
+];
+
+const CURVE_POINT_FIELDS: [(u64, &str); 10] = [
+    (0x00, "Position X"),
+    (0x04, "Position Y"),
+    (0x08, "Position Z"),
+    (0x0C, "Handle 0 X"),
+    (0x10, "Handle 0 Y"),
+    (0x14, "Handle 0 Z"),
+    (0x18, "Handle 1 X"),
+    (0x1C, "Handle 1 Y"),
+    (0x20, "Handle 1 Z"),
+    (0x24, "Unknown"),
+];
+
+const EFFECT_GROUP_FIELDS: [(u64, &str); 15] = [
+    (0x00, "Effect Count"),
+    (0x02, "Offset[X02]"),
+    (0x04, "Offset[X04]"),
+    (0x06, "Offset[X06]"),
+    (0x08, "Offset[X08]"),
+    (0x0A, "Offset[X0A]"),
+    (0x0B, "Offset[X0B]"),
+    (0x0C, "Offset[X0C]"),
+    (0x10, "Offset[X10]"),
+    (0x14, "Offset[X14]"),
+    (0x18, "Offset[X18]"),
+    (0x1C, "Offset[X1C]"),
+    (0x20, "Offset[X20]"),
+    (0x24, "Offset[X24]"),
+    (0x25, "Offset[X25]"),
+];
+
+const EFFECT_FIELDS: [(u64, &str); 113] = [
+    (0x000, "State ID"),
+    (0x001, "ESP ID"),
+    (0x002, "Texture ID"),
+    (0x003, "Unknown X03"),
+    (0x004, "Delay"),
+    (0x006, "Parent"),
+    (0x007, "Parent Part"),
+    (0x008, "Flags"),
+    (0x00C, "Position X"),
+    (0x010, "Position Y"),
+    (0x014, "Position Z"),
+    (0x018, "Random Position X"),
+    (0x01C, "Random Position Y"),
+    (0x020, "Random Position Z"),
+    (0x024, "Speed X"),
+    (0x028, "Speed Y"),
+    (0x02C, "Speed Z"),
+    (0x030, "Delta Speed"),
+    (0x034, "Random Speed X"),
+    (0x038, "Random Speed Y"),
+    (0x03C, "Random Speed Z"),
+    (0x040, "Acceleration X"),
+    (0x044, "Acceleration Y"),
+    (0x048, "Acceleration Z"),
+    (0x04C, "Random Acceleration X"),
+    (0x050, "Random Acceleration Y"),
+    (0x054, "Random Acceleration Z"),
+    (0x058, "Rotation X"),
+    (0x05C, "Rotation Y"),
+    (0x060, "Rotation Z"),
+    (0x064, "Random Rotation X"),
+    (0x068, "Random Rotation Y"),
+    (0x06C, "Random Rotation Z"),
+    (0x070, "Rotation Acceleration X"),
+    (0x074, "Rotation Acceleration Y"),
+    (0x078, "Rotation Acceleration Z"),
+    (0x07C, "Random Rotation Acceleration X"),
+    (0x080, "Random Rotation Acceleration Y"),
+    (0x084, "Random Rotation Acceleration Z"),
+    (0x088, "Width"),
+    (0x08C, "Height"),
+    (0x090, "Random Size"),
+    (0x094, "Grow"),
+    (0x098, "Delta Grow"),
+    (0x09C, "R"),
+    (0x09D, "G"),
+    (0x09E, "B"),
+    (0x09F, "A"),
+    (0x0A0, "Delta R"),
+    (0x0A4, "Delta G"),
+    (0x0A8, "Delta B"),
+    (0x0AC, "Delta A"),
+    (0x0B0, "Delta Color Max Frame"),
+    (0x0B2, "Delta Color Start Frame"),
+    (0x0B4, "Unknown XB4"),
+    (0x0B6, "Delta Size Start Frame"),
+    (0x0B8, "Lifetime"),
+    (0x0BA, "Animation Speed"),
+    (0x0BE, "Unknown XBE"),
+    (0x0C0, "Release Time"),
+    (0x0C1, "Blend"),
+    (0x0C3, "Simulation Type"),
+    (0x0C4, "Simulation Power"),
+    (0x0C5, "Mask Texture ID"),
+    (0x0C6, "Value In"),
+    (0x0C7, "Value Out"),
+    (0x0C8, "Work 0"),
+    (0x0C9, "Work 1"),
+    (0x0CA, "Work 2"),
+    (0x0CB, "Work 3"),
+    (0x0CC, "Work 4"),
+    (0x0D0, "Work 5"),
+    (0x0D4, "Work 6"),
+    (0x0D8, "Vector 0 X"),
+    (0x0DC, "Vector 0 Y"),
+    (0x0E0, "Vector 0 Z"),
+    (0x0E4, "Vector 1 X"),
+    (0x0E8, "Vector 1 Y"),
+    (0x0EC, "Vector 1 Z"),
+    (0x0F0, "Vector 2 X"),
+    (0x0F4, "Vector 2 Y"),
+    (0x0F8, "Vector 2 Z"),
+    (0x0FC, "Spline 0"),
+    (0x0FD, "Spline 1"),
+    (0x0FE, "Spline 2"),
+    (0x0FF, "Spline 3"),
+    (0x100, "Unknown X100"),
+    (0x104, "Path Own"),
+    (0x105, "Path Number"),
+    (0x106, "Path Start"),
+    (0x107, "Path Random"),
+    (0x108, "Effect Type"),
+    (0x109, "Control ID"),
+    (0x10A, "Control Flag"),
+    (0x10C, "Control Interval"),
+    (0x10D, "Control Number"),
+    (0x10E, "Control RP"),
+    (0x10F, "Unknown X10F"),
+    (0x110, "Control Life"),
+    (0x112, "Unknown X112"),
+    (0x114, "Unknown X114"),
+    (0x116, "Unknown X116"),
+    (0x118, "Control Path Scale X"),
+    (0x11C, "Control Path Scale Y"),
+    (0x120, "Control Path Scale Z"),
+    (0x124, "Control Path Delta Size"),
+    (0x125, "Control Path Delta Speed"),
+    (0x126, "Control Path Delta Alpha"),
+    (0x127, "Control Path Delta Interval"),
+    (0x128, "Control Path Random Interval"),
+    (0x129, "Control Path Rotation X"),
+    (0x12A, "Control Path Rotation Y"),
+    (0x12B, "Control Path Flag"),
+];
+
+/// First byte where a rebuilt file differs from the original.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mismatch {
+    pub offset: u64,
+    /// `None` when the file ends before `offset`.
+    pub original: Option<u8>,
+    pub rebuilt: Option<u8>,
+    /// Where `offset` falls in the original file, e.g. "Effect 1 / Group 3 / Effect 7 / Blend".
+    pub location: String,
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let byte = |value: Option<u8>| match value {
+            Some(value) => format!("0x{:02X}", value),
+            None => "end of file".to_owned(),
+        };
+        write!(
+            f,
+            "0x{:X} ({}): original {}, rebuilt {}",
+            self.offset,
+            self.location,
+            byte(self.original),
+            byte(self.rebuilt)
+        )
+    }
+}
+
+// pasta temporaria unica, mesmo com varias verificacoes ao mesmo tempo
+fn temp_dir() -> PathBuf {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+    std::env::temp_dir().join(format!(
+        "re4_effblob_verify_{}_{}",
+        std::process::id(),
+        COUNTER.fetch_add(1, Ordering::Relaxed)
+    ))
+}
+
+/// Loads `original`, extracts it to text in a temporary folder, reads the text back
+/// and compiles it again. Returns the first differing byte, or `None` when the
+/// rebuilt file is identical.
+pub fn verify(original: &[u8], endianness: &Endian<()>) -> Result<Option<Mismatch>> {
+    let eff = Eff::load(&mut Cursor::new(original), endianness)?;
+
+    let dir = temp_dir();
+    let rebuilt = write_to_text(&eff, &dir.join("verify"), &Manifest::new(&eff, endianness, Some(original)))
+        .and_then(|_| read_from_text(&dir.join("verify")))
+        .and_then(|eff| eff.compile(endianness));
+    let _ = std::fs::remove_dir_all(&dir);
+    let rebuilt = rebuilt?;
+
+    let offset = match original.iter().zip(&rebuilt).position(|(a, b)| a != b) {
+        Some(offset) => offset,
+        None if original.len() == rebuilt.len() => return Ok(None),
+        None => original.len().min(rebuilt.len()),
+    };
+
+    Ok(Some(Mismatch {
+        offset: offset as u64,
+        original: original.get(offset).copied(),
+        rebuilt: rebuilt.get(offset).copied(),
+        location: locate(original, offset as u64, endianness),
+    }))
+}
+
+fn read<T: Copy + Default>(bytes: &[u8], offset: u64, endianness: &Endian<()>) -> Option<T> {
+    let mut cursor = Cursor::new(bytes);
+    cursor.set_position(offset);
+    Endian::<T>::from_stream(&mut cursor)?.cast(endianness)
+}
+
+// nome do campo que contem o offset relativo
+fn field_name(fields: &[(u64, &str)], offset: u64) -> String {
+    match fields.iter().rev().find(|(start, _)| *start <= offset) {
+        Some((start, name)) if *start == offset => name.to_string(),
+        Some((start, name)) => format!("{} +0x{:X}", name, offset - start),
+        None => format!("+0x{:X}", offset),
+    }
+}
+
+// elementos de tamanho fixo depois de um contador u32
+fn locate_table(count: u64, offset: u64, fields: &[(u64, &str)]) -> String {
+    if offset < 4 {
+        return "Entry Count".to_owned();
+    }
+    let index = (offset - 4) / TABLE_ENTRY_SIZE;
+    if index >= count {
+        return "Padding".to_owned();
+    }
+    format!("Entry {} / {}", index, field_name(fields, (offset - 4) % TABLE_ENTRY_SIZE))
+}
+
+// tabela de offsets (contador + offsets relativos ao inicio da secao) seguida dos blocos;
+// retorna o indice do bloco, o inicio dele e o offset dentro dele
+fn locate_block(
+    bytes: &[u8],
+    start: u64,
+    offset: u64,
+    endianness: &Endian<()>,
+) -> std::result::Result<(usize, u64, u64), String> {
+    let count = read::<u32>(bytes, start, endianness).unwrap_or_default() as u64;
+    if offset < 4 {
+        return Err("Entry Count".to_owned());
+    }
+    if offset < 4 + count * 4 {
+        return Err(format!("Offset {}", (offset - 4) / 4));
+    }
+
+    let mut found = None;
+    for index in 0..count {
+        match read::<u32>(bytes, start + 4 + index * 4, endianness) {
+            Some(block) if block as u64 <= offset => {
+                found = Some((index as usize, start + block as u64, offset - block as u64))
+            }
+            _ => {}
+        }
+    }
+    found.ok_or_else(|| "Padding".to_owned())
+}
+
+fn locate_effect_group(count: u16, offset: u64) -> String {
+    if offset < EFFECT_GROUP_HEADER_SIZE {
+        return field_name(&EFFECT_GROUP_FIELDS, offset);
+    }
+    let offset = offset - EFFECT_GROUP_HEADER_SIZE;
+    if offset / EFFECT_SIZE >= count as u64 {
+        return "Padding".to_owned();
+    }
+    format!(
+        "Effect {} / {}",
+        offset / EFFECT_SIZE,
+        field_name(&EFFECT_FIELDS, offset % EFFECT_SIZE)
+    )
+}
+
+fn locate_curve(count: u16, offset: u64) -> String {
+    if offset < 4 {
+        return field_name(&[(0, "Point Count"), (2, "Padding")], offset);
+    }
+    let offset = offset - 4;
+    if offset / CURVE_POINT_SIZE >= count as u64 {
+        return "Padding".to_owned();
+    }
+    format!(
+        "Point {} / {}",
+        offset / CURVE_POINT_SIZE,
+        field_name(&CURVE_POINT_FIELDS, offset % CURVE_POINT_SIZE)
+    )
+}
+
+/// Maps an offset of an EFFBLOB file to the section, group, effect and field it falls in.
+fn locate(bytes: &[u8], offset: u64, endianness: &Endian<()>) -> String {
+    if offset < 4 {
+        return "Header / Section Count".to_owned();
+    }
+    if offset < 0x40 {
+        match SECTION_NAMES.get((offset as usize - 4) / 4) {
+            Some(name) => return format!("Header / {} Offset", name),
+            None => return "Header / Padding".to_owned(),
+        }
+    }
+
+    // secao com o maior inicio antes do offset
+    let section = (0..SECTION_NAMES.len())
+        .filter_map(|index| {
+            let start = read::<u32>(bytes, 4 + index as u64 * 4, endianness)? as u64;
+            (start != 0 && start <= offset).then_some((index, start))
+        })
+        .max_by_key(|(_, start)| *start);
+    let Some((index, start)) = section else {
+        return "Header / Padding".to_owned();
+    };
+
+    let relative = offset - start;
+    let count = read::<u32>(bytes, start, endianness).unwrap_or_default() as u64;
+    // contador de elementos no inicio de um bloco
+    let block_count = |block_start: u64| read::<u16>(bytes, block_start, endianness).unwrap_or_default();
+    let detail = match index {
+        0 | 1 | 3 | 4 => locate_table(count, relative, &TABLE_ENTRY_FIELDS),
+        2 => locate_table(count, relative, &EAR_LINK_FIELDS),
+        6 => match locate_block(bytes, start, relative, endianness) {
+            Ok((block, _, inner)) if inner < 0xC => {
+                format!("Texture {} / {}", block, field_name(&TEXTURE_METADATA_FIELDS, inner))
+            }
+            Ok(_) => "Padding".to_owned(),
+            Err(name) => name,
+        },
+        7 | 8 => match locate_block(bytes, start, relative, endianness) {
+            Ok((block, block_start, inner)) => {
+                format!("Group {} / {}", block, locate_effect_group(block_count(block_start), inner))
+            }
+            Err(name) => name,
+        },
+        9 => match locate_block(bytes, start, relative, endianness) {
+            Ok((block, block_start, inner)) => {
+                format!("Path {} / {}", block, locate_curve(block_count(block_start), inner))
+            }
+            Err(name) => name,
+        },
+        _ => format!("Byte 0x{:X}", relative),
+    };
+
+    format!("{} / {}", SECTION_NAMES[index], detail)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eff::sample;
+
+    #[test]
+    fn identical_after_round_trip() {
+        let bytes = sample::eff().compile(&Endian::Little(())).unwrap();
+        assert_eq!(verify(&bytes, &Endian::Little(())).unwrap(), None);
+    }
+
+    #[test]
+    fn raw_tables_round_trip() {
+        let mut eff = sample::eff();
+        // Table 5 sem alinhamento: o padding ate a proxima secao tambem tem que voltar igual
+        eff.table_5 = (1..=13).collect();
+        eff.table_10 = (0..0x40u8).map(|byte| byte.wrapping_mul(7) ^ 0xA5).collect();
+
+        for endianness in [Endian::Little(()), Endian::Big(())] {
+            let original = eff.compile(&endianness).unwrap();
+            let loaded = Eff::load(&mut Cursor::new(&original), &endianness).unwrap();
+            assert_eq!(loaded.table_5[..13], eff.table_5[..]);
+            assert_eq!(loaded.table_10, eff.table_10);
+
+            let dir = temp_dir();
+            let folder = dir.join("raw");
+            write_to_text(&loaded, &folder, &Manifest::new(&loaded, &endianness, Some(&original))).unwrap();
+            let manifest = Manifest::read(&folder).unwrap();
+            let rebuilt = read_from_text(&folder).and_then(|eff| eff.compile(&endianness));
+            let warnings = manifest.check(&folder);
+            let _ = std::fs::remove_dir_all(&dir);
+
+            assert_eq!(manifest.sections, (0..SECTION_NAMES.len()).collect::<Vec<_>>());
+            assert_eq!(warnings, Vec::<String>::new());
+            let name = if endianness.is_big() { "big" } else { "little" };
+            assert!(rebuilt.unwrap() == original, "{} endian rebuild differs", name);
+        }
+    }
+
+    #[test]
+    fn mismatch_in_header_padding() {
+        let mut bytes = sample::eff().compile(&Endian::Big(())).unwrap();
+        // o padding do cabecalho nao é lido, volta como zero
+        bytes[0x3C] = 0xAB;
+        let mismatch = verify(&bytes, &Endian::Big(())).unwrap().unwrap();
+        assert_eq!(mismatch.offset, 0x3C);
+        assert_eq!(mismatch.original, Some(0xAB));
+        assert_eq!(mismatch.rebuilt, Some(0));
+        assert_eq!(mismatch.location, "Header / Padding");
+    }
+
+    #[test]
+    fn locate_effect_field() {
+        let endianness = Endian::Little(());
+        let bytes = sample::eff().compile(&endianness).unwrap();
+        let section = read::<u32>(&bytes, 4 + 8 * 4, &endianness).unwrap() as u64;
+        let group = section + read::<u32>(&bytes, section + 4, &endianness).unwrap() as u64;
+        let blend = group + EFFECT_GROUP_HEADER_SIZE + 0xC1;
+        assert_eq!(locate(&bytes, blend, &endianness), "Effect 1 / Group 0 / Effect 0 / Blend");
+        assert_eq!(
+            locate(&bytes, group + EFFECT_GROUP_HEADER_SIZE + EFFECT_SIZE + 2, &endianness),
+            "Effect 1 / Group 0 / Padding"
+        );
+        assert_eq!(locate(&bytes, 4 + 9 * 4, &endianness), "Header / Table 9 (Paths) Offset");
+    }
+}
@@ -0,0 +1,30 @@
+//! Library for reading and writing RE4 EFFBLOB/EFFBLOBBIG files.
+//!
+//! The binary side is handled by [`Eff::load`] and [`Eff::save`] (or [`Eff::compile`]
+//! to get the raw bytes), the extracted txt2 folder layout by [`write_to_text`]
+//! and [`read_from_text`]. The byte order of a file can be found with
+//! [`Eff::detect_endianness`]; it is recorded, with the other extraction details,
+//! in the [`Manifest`] written next to the folder.
+//!
+//! ```no_run
+//! use re4_effblob::{Eff, Manifest};
+//! use std::io::BufReader;
+//!
+//! let mut reader = BufReader::new(std::fs::File::open("core_001.EFFBLOB").unwrap());
+//! let endianness = Eff::detect_endianness(&mut reader).unwrap();
+//! let eff = Eff::load(&mut reader, &endianness).unwrap();
+//! let manifest = Manifest::new(&eff, &endianness, None);
+//! re4_effblob::write_to_text(&eff, std::path::Path::new("core_001"), &manifest).unwrap();
+//! ```
+
+mod eff;
+
+pub use eff::manifest::{manifest_path, sha256_hex, Manifest, LAYOUT_VERSION, TOOL_VERSION};
+pub use eff::text::{read_from_text, read_from_text_with_warnings, write_to_text};
+pub use eff::validate::{Issue, Severity};
+pub use eff::{csv, diff, gltf, info, json, merge, paths, verify};
+pub use eff::{
+    Blend, ControlFlags, ControlPathFlags, Curve, CurvePoint, EarLink, Eff, EffError, Effect, EffectFlags, EffectGroup,
+    EffectType, Platform, Result, SimulationType, TableEntry, TextureMetadata, SECTION_NAMES,
+};
+pub use scalar_types::Endian;
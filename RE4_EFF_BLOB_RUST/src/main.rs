@@ -1,46 +1,657 @@
-use scalar_types::Endian;
-use std::io::{BufReader, Result, Write};
+use re4_effblob::{manifest_path, sha256_hex, Eff, EffError, Manifest, Platform, TOOL_VERSION};
+use re4_effblob::Endian;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
 use std::env;
 
-pub mod eff;
+mod batch;
+mod cli;
 
-fn main() -> Result<()> {
-    let mut args: Vec<String> = env::args().collect();
+fn read_file(path: &Path) -> Result<Vec<u8>, EffError> {
+    std::fs::read(path).map_err(|error| EffError::Io {
+        path: Some(path.into()),
+        source: error,
+    })
+}
+
+fn write_file(path: &Path, bytes: &[u8]) -> Result<(), EffError> {
+    std::fs::write(path, bytes).map_err(|error| EffError::Io {
+        path: Some(path.into()),
+        source: error,
+    })
+}
+
+fn extension(endianness: &Endian<()>) -> &'static str {
+    if endianness.is_big() {
+        ".EFFBLOBBIG"
+    } else {
+        ".EFFBLOB"
+    }
+}
+
+// "core_001" + ".EFFBLOB"; components() ignora a barra no final de uma pasta
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut file = path.components().as_path().as_os_str().to_owned();
+    file.push(suffix);
+    PathBuf::from(file)
+}
+
+// endianness None = detectar pelo cabecalho do arquivo
+fn load(file: &Path, endianness: Option<Endian<()>>) -> Result<(Eff, Endian<()>, Vec<u8>), EffError> {
+    let bytes = read_file(file)?;
+    let mut reader = Cursor::new(&bytes);
+    let context = || file.display().to_string();
+    let endianness = match endianness {
+        Some(endianness) => endianness,
+        None => Eff::detect_endianness(&mut reader).map_err(|error| error.context(context()))?,
+    };
+    let x = Eff::load(&mut reader, &endianness).map_err(|error| error.context(context()))?;
+    Ok((x, endianness, bytes))
+}
+
+// a plataforma define a ordem dos bytes; dadas as duas, precisam concordar
+fn platform_endianness(platform: Option<Platform>, endianness: Option<Endian<()>>) -> Result<Option<Endian<()>>, EffError> {
+    match (platform, endianness) {
+        (Some(platform), Some(endianness)) if platform.is_big() != endianness.is_big() => {
+            Err(EffError::PlatformByteOrder { platform })
+        }
+        (Some(platform), None) => Ok(Some(platform.endianness())),
+        (_, endianness) => Ok(endianness),
+    }
+}
+
+// load, conferindo que o arquivo esta na ordem de bytes de platform
+fn load_for(
+    file: &Path,
+    endianness: Option<Endian<()>>,
+    platform: Option<Platform>,
+) -> Result<(Eff, Endian<()>, Vec<u8>), EffError> {
+    let (x, endianness, bytes) = load(file, endianness)?;
+    match platform {
+        Some(platform) if platform.is_big() != endianness.is_big() => {
+            Err(EffError::PlatformByteOrder { platform }.context(file.display().to_string()))
+        }
+        _ => Ok((x, endianness, bytes)),
+    }
+}
+
+// platform vai para o .EFFBLOBTXT, o repack usa ela
+fn extract(file: &Path, path: &Path, endianness: Option<Endian<()>>, platform: Option<Platform>) -> Result<(), EffError> {
+    let (x, endianness, bytes) = load_for(file, endianness, platform)?;
+    let mut manifest = Manifest::new(&x, &endianness, Some(&bytes));
+    manifest.platform = platform;
+    re4_effblob::write_to_text(&x, path, &manifest)
+}
+
+fn extract_json(file: &Path, path: &Path, endianness: Option<Endian<()>>, platform: Option<Platform>) -> Result<(), EffError> {
+    let (x, _, _) = load_for(file, endianness, platform)?;
+    re4_effblob::json::write_to_json(&x, path)
+}
+
+fn extract_gltf(file: &Path, path: &Path, endianness: Option<Endian<()>>, platform: Option<Platform>) -> Result<(), EffError> {
+    let (x, _, _) = load_for(file, endianness, platform)?;
+    re4_effblob::gltf::write_to_gltf(&x, path)
+}
+
+fn extract_csv(file: &Path, path: &Path, endianness: Option<Endian<()>>, platform: Option<Platform>) -> Result<(), EffError> {
+    let (x, _, _) = load_for(file, endianness, platform)?;
+    re4_effblob::csv::write_to_csv(&x, path)
+}
+
+// mostra o resultado do validate; retorna a quantidade de erros
+fn report(x: &Eff, platform: Option<Platform>, log: &mut Vec<String>) -> usize {
+    let issues = platform.map_or_else(|| x.validate(), |platform| x.validate_for(platform));
+    for issue in &issues {
+        log.push(format!("# {}", issue));
+    }
+    issues.iter().filter(|issue| issue.is_error()).count()
+}
+
+// validate antes de gravar: com erros o arquivo nao é gravado, a menos que validate seja false;
+// com platform, endianness tem que ser a dela (platform_endianness)
+fn compile(
+    x: &Eff,
+    endianness: &Endian<()>,
+    platform: Option<Platform>,
+    validate: bool,
+    log: &mut Vec<String>,
+) -> Result<Vec<u8>, EffError> {
+    let errors = report(x, platform, log);
+    if errors > 0 && validate {
+        log.push("# Fix the errors above, or use --no-validate to write the file anyway".to_owned());
+        return Err(EffError::Invalid { errors });
+    }
+    match platform {
+        Some(platform) => x.compile_for(platform),
+        None => x.compile(endianness),
+    }
+}
+
+// Table 5 e Table 10 nao foram pesquisadas, os bytes ficam na ordem em que foram lidos; trocar a
+// ordem do arquivo só copiando esses bytes como estao, com copy_raw. Tabelas so com zeros
+// (as vazias sao gravadas como 0x20 zeros) sao iguais nas duas ordens
+fn check_raw_tables(
+    x: &Eff,
+    source: &Endian<()>,
+    target: &Endian<()>,
+    copy_raw: bool,
+    log: &mut Vec<String>,
+) -> Result<(), EffError> {
+    let zeros = |table: &[u8]| table.iter().all(|byte| *byte == 0);
+    if source.is_big() == target.is_big() || (zeros(&x.table_5) && zeros(&x.table_10)) {
+        return Ok(());
+    }
+    if copy_raw {
+        log.push("# Warning: Table 5 and Table 10 are copied as raw bytes, in the byte order of the source".to_owned());
+        return Ok(());
+    }
+    log.push("# Use --copy-raw to copy Table 5 and Table 10 unchanged anyway".to_owned());
+    Err(EffError::RawTables { big: target.is_big() })
+}
+
+// endianness None = pela extensao da saida, .EFFBLOBBIG é big endian
+fn output_endianness(output: &Path, endianness: Option<Endian<()>>) -> Endian<()> {
+    match endianness {
+        Some(endianness) => endianness,
+        None if output.to_string_lossy().to_uppercase().ends_with(".EFFBLOBBIG") => Endian::Big(()),
+        None => Endian::Little(()),
+    }
+}
+
+fn repack_json(
+    file: &Path,
+    output: Option<&Path>,
+    endianness: Option<Endian<()>>,
+    platform: Option<Platform>,
+    validate: bool,
+    log: &mut Vec<String>,
+) -> Result<PathBuf, EffError> {
+    let endianness = platform_endianness(platform, endianness)?;
+    let endianness = match output {
+        Some(output) => output_endianness(output, endianness),
+        None => endianness.unwrap_or(Endian::Little(())),
+    };
+    let suffix = platform.map_or_else(|| extension(&endianness), Platform::extension);
+    let output = output.map_or_else(|| file.with_extension(&suffix[1..]), Path::to_path_buf);
+    let x = re4_effblob::json::read_from_json(file)?;
+    write_file(&output, &compile(&x, &endianness, platform, validate, log)?)?;
+    Ok(output)
+}
+
+// aplica os csv da pasta sobre o arquivo e grava na mesma ordem de bytes
+fn repack_csv(file: &Path, path: &Path, output: &Path, validate: bool, log: &mut Vec<String>) -> Result<(), EffError> {
+    let (mut x, endianness, _) = load(file, None)?;
+    for warning in re4_effblob::csv::read_from_csv(&mut x, path)? {
+        log.push(format!("# Warning: {}", warning));
+    }
+    write_file(output, &compile(&x, &endianness, None, validate, log)?)
+}
+
+fn write_schema(path: &Path) -> Result<(), EffError> {
+    write_file(path, re4_effblob::json::schema().as_bytes())
+}
+
+// endianness None = usar a ordem gravada no .EFFBLOBTXT; platform None = a do .EFFBLOBTXT;
+// output None = pasta + extensao; copy_raw = aceitar Table 5 e Table 10 na ordem da extracao
+fn repack(
+    file: &Path,
+    output: Option<&Path>,
+    endianness: Option<Endian<()>>,
+    platform: Option<Platform>,
+    validate: bool,
+    copy_raw: bool,
+    log: &mut Vec<String>,
+) -> Result<PathBuf, EffError> {
+    // sem o .EFFBLOBTXT a pasta é do layout 1, a ordem dos bytes precisa vir da linha de comando
+    let manifest = if manifest_path(file).is_file() {
+        Manifest::read(file)?
+    } else {
+        Manifest::legacy()
+    };
+    for warning in manifest.check(file) {
+        log.push(format!("# Warning: {}", warning));
+    }
+
+    // a plataforma do .EFFBLOBTXT nao vale quando a ordem pedida é a outra (conversao)
+    let from_manifest = manifest
+        .platform
+        .filter(|found| endianness.as_ref().is_none_or(|endianness| endianness.is_big() == found.is_big()));
+    let platform = platform.or(from_manifest);
+    let endianness = match (platform_endianness(platform, endianness)?, &manifest.endianness) {
+        (Some(endianness), _) => endianness,
+        (None, Some(Endian::Big(()))) => Endian::Big(()),
+        (None, Some(_)) => Endian::Little(()),
+        (None, None) => return Err(EffError::MissingByteOrder { path: manifest_path(file) }),
+    };
+    let suffix = platform.map_or_else(|| extension(&endianness), Platform::extension);
+    let output = output.map_or_else(|| with_suffix(file, suffix), Path::to_path_buf);
+    let (effect_file, warnings) = re4_effblob::read_from_text_with_warnings(file)?;
+    for warning in warnings {
+        log.push(format!("# Warning: {}", warning));
+    }
+    // sem o .EFFBLOBTXT nao se sabe a ordem em que as tabelas foram extraidas
+    if let Some(extracted) = &manifest.endianness {
+        check_raw_tables(&effect_file, extracted, &endianness, copy_raw, log)?;
+    }
+    let bytes = compile(&effect_file, &endianness, platform, validate, log)?;
+    if manifest.source_sha256.as_deref() == Some(sha256_hex(&bytes).as_str()) {
+        log.push("# Identical to the extracted file".to_owned());
+    }
+    write_file(&output, &bytes)?;
+    Ok(output)
+}
+
+// extract + repack em memoria, comparando com o arquivo original; false = diferente
+fn verify(file: &Path, endianness: Option<Endian<()>>, log: &mut Vec<String>) -> Result<bool, EffError> {
+    let bytes = read_file(file)?;
+    let context = || file.display().to_string();
+    let endianness = match endianness {
+        Some(endianness) => endianness,
+        None => Eff::detect_endianness(&mut Cursor::new(&bytes)).map_err(|error| error.context(context()))?,
+    };
+    match re4_effblob::verify::verify(&bytes, &endianness).map_err(|error| error.context(context()))? {
+        None => {
+            log.push(format!("# Identical: {}", file.display()));
+            Ok(true)
+        }
+        Some(mismatch) => {
+            log.push(format!("# Mismatch: {}: {}", file.display(), mismatch));
+            Ok(false)
+        }
+    }
+}
 
-    println!("# RE4_EFF_BLOB_RUST"); 
-    println!("# Tool By Zatarita"); 
-    println!("# Fork By JADERLINK"); 
-    println!("# Version 1.1.0"); 
+// o arquivo, a pasta extraida (ou o .EFFBLOBTXT) ou o json; platform so confere o arquivo
+fn load_any(
+    file: &Path,
+    endianness: Option<Endian<()>>,
+    platform: Option<Platform>,
+    log: &mut Vec<String>,
+) -> Result<Eff, EffError> {
+    let name = file.to_string_lossy().to_uppercase();
+    if name.ends_with(".JSON") {
+        return re4_effblob::json::read_from_json(file);
+    }
+    if name.ends_with(".EFFBLOBTXT") || file.is_dir() {
+        let folder = if file.is_dir() { file.to_path_buf() } else { file.with_extension("") };
+        let (x, warnings) = re4_effblob::read_from_text_with_warnings(&folder)?;
+        for warning in warnings {
+            log.push(format!("# Warning: {}", warning));
+        }
+        return Ok(x);
+    }
+    Ok(load_for(file, endianness, platform)?.0)
+}
+
+// false = tem erros; platform None = a do .EFFBLOBTXT, quando é uma pasta extraida
+fn validate(
+    file: &Path,
+    endianness: Option<Endian<()>>,
+    platform: Option<Platform>,
+    log: &mut Vec<String>,
+) -> Result<bool, EffError> {
+    let x = load_any(file, endianness, platform, log)?;
+    let platform = match platform {
+        None if file.is_dir() && manifest_path(file).is_file() => Manifest::read(file)?.platform,
+        None if file.to_string_lossy().to_uppercase().ends_with(".EFFBLOBTXT") => {
+            Manifest::read(&file.with_extension(""))?.platform
+        }
+        platform => platform,
+    };
+    let valid = report(&x, platform, log) == 0;
+    if valid {
+        log.push(format!("# Valid: {}", file.display()));
+    }
+    Ok(valid)
+}
 
-    let path = args.pop().unwrap();
-    let file = args.pop().unwrap();
-    let argument = args.pop().unwrap();
+// diferencas entre os dois arquivos, em texto ou um documento json; false = sao diferentes
+fn diff(
+    old: &Path,
+    new: &Path,
+    endianness: Option<Endian<()>>,
+    tolerance: re4_effblob::diff::Tolerance,
+    json: bool,
+    log: &mut Vec<String>,
+) -> Result<bool, EffError> {
+    // Endian nao é Clone
+    let same = endianness.as_ref().map(|endianness| if endianness.is_big() { Endian::Big(()) } else { Endian::Little(()) });
+    let old_eff = load_any(old, endianness, None, log)?;
+    let new_eff = load_any(new, same, None, log)?;
+    let changes = re4_effblob::diff::diff(&old_eff, &new_eff, tolerance);
 
-    if argument == "-eLittle" { //extracao little
-        let file = std::fs::File::open(&file)?;
-        let mut reader = BufReader::new(file);
-        let mut x = eff::Eff::new(&mut reader, &Endian::Little(())).unwrap();
-        x.write_to_text(std::path::Path::new(&path))?;
+    if json {
+        #[derive(serde::Serialize)]
+        struct Document<'a> {
+            old: String,
+            new: String,
+            changes: &'a [re4_effblob::diff::Change],
+        }
+        let document = Document {
+            old: old.display().to_string(),
+            new: new.display().to_string(),
+            changes: &changes,
+        };
+        let text = serde_json::to_string_pretty(&document).map_err(|error| EffError::Json {
+            path: None,
+            source: error,
+        })?;
+        println!("{}", text);
+    } else {
+        println!("--- {}", old.display());
+        println!("+++ {}", new.display());
+        for change in &changes {
+            println!("{}", change);
+        }
+        match changes.len() {
+            0 => println!("# No differences"),
+            count => println!("# {} differences", count),
+        }
     }
+    Ok(changes.is_empty())
+}
+
+// merge de tres versoes; false = teve conflitos, o arquivo é gravado com o lado de prefer
+fn merge(
+    [base, ours, theirs]: [&Path; 3],
+    output: &Path,
+    endianness: Option<Endian<()>>,
+    prefer: re4_effblob::merge::Side,
+    report: Option<&Path>,
+    validate: bool,
+    log: &mut Vec<String>,
+) -> Result<bool, EffError> {
+    let base = load_any(base, None, None, log)?;
+    let ours = load_any(ours, None, None, log)?;
+    let theirs = load_any(theirs, None, None, log)?;
+    let (merged, conflicts) = re4_effblob::merge::merge(&base, &ours, &theirs, prefer)?;
 
-    if argument == "-eBig" { //extracao big
-        let file = std::fs::File::open(&file)?;
-        let mut reader = BufReader::new(file);
-        let mut x = eff::Eff::new(&mut reader, &Endian::Big(())).unwrap();
-        x.write_to_text(std::path::Path::new(&path))?;
+    if output.extension().is_some_and(|extension| extension.eq_ignore_ascii_case("json")) {
+        re4_effblob::json::write_to_json(&merged, output)?;
+    } else {
+        let endianness = output_endianness(output, endianness);
+        write_file(output, &compile(&merged, &endianness, None, validate, log)?)?;
     }
 
-    if argument == "-bLittle" { //repack little
-        let mut effect_file = eff::Eff::read_from_text(std::path::Path::new(&file)).unwrap();
-        std::fs::write(&path, effect_file.compile(&Endian::Little(())).unwrap());
-    }  
-    
-    if argument == "-bBig" { //repack big
-        let mut effect_file = eff::Eff::read_from_text(std::path::Path::new(&file)).unwrap();
-        std::fs::write(&path, effect_file.compile(&Endian::Big(())).unwrap());
+    if conflicts.is_empty() {
+        return Ok(true);
     }
+    let report = report.map_or_else(|| with_suffix(output, ".conflicts.txt"), Path::to_path_buf);
+    let text = if report.extension().is_some_and(|extension| extension.eq_ignore_ascii_case("json")) {
+        serde_json::to_string_pretty(&conflicts).map_err(|error| EffError::Json {
+            path: Some(report.clone()),
+            source: error,
+        })?
+    } else {
+        conflicts.iter().map(|conflict| format!("{}\n", conflict)).collect()
+    };
+    write_file(&report, text.as_bytes())?;
+    for conflict in &conflicts {
+        log.push(format!("# Conflict: {}", conflict));
+    }
+    log.push(format!("# {} conflicts, written to {}", conflicts.len(), report.display()));
+    Ok(false)
+}
 
-     println!("# Finished!!!");
+// os paths da Table 9 em .obj e/ou .svg; output None = o nome da entrada + "_paths", com os dois;
+// uma saida .obj ou .svg grava so esse formato
+fn export_paths(
+    file: &Path,
+    output: Option<&Path>,
+    endianness: Option<Endian<()>>,
+    samples: usize,
+    log: &mut Vec<String>,
+) -> Result<Vec<PathBuf>, EffError> {
+    let x = load_any(file, endianness, None, log)?;
+    let output = output.map_or_else(|| with_suffix(&file.with_extension(""), "_paths"), Path::to_path_buf);
+    let format = output.extension().map(|extension| extension.to_string_lossy().to_lowercase());
+    let mut written = Vec::new();
+    if format.as_deref() != Some("svg") {
+        let obj = if format.as_deref() == Some("obj") { output.clone() } else { with_suffix(&output, ".obj") };
+        re4_effblob::paths::write_paths_to_obj(&x.paths, samples, &obj)?;
+        written.push(obj);
+    }
+    if format.as_deref() != Some("obj") {
+        let svg = if format.as_deref() == Some("svg") { output.clone() } else { with_suffix(&output, ".svg") };
+        re4_effblob::paths::write_paths_to_svg(&x.paths, &svg)?;
+        written.push(svg);
+    }
+    if x.paths.is_empty() {
+        log.push(format!("# Warning: {}: Table 9 (Paths) is empty", file.display()));
+    }
+    Ok(written)
+}
+
+// resumo do arquivo em texto, ou um documento json
+fn info(file: &Path, endianness: Option<Endian<()>>, json: bool) -> Result<(), EffError> {
+    let bytes = read_file(file)?;
+    let context = || file.display().to_string();
+    let endianness = match endianness {
+        Some(endianness) => endianness,
+        None => Eff::detect_endianness(&mut Cursor::new(&bytes)).map_err(|error| error.context(context()))?,
+    };
+    let summary = re4_effblob::info::Info::new(&bytes, &endianness).map_err(|error| error.context(context()))?;
 
+    if json {
+        #[derive(serde::Serialize)]
+        struct FileInfo<'a> {
+            file: String,
+            #[serde(flatten)]
+            info: &'a re4_effblob::info::Info,
+        }
+        let document = FileInfo {
+            file: file.display().to_string(),
+            info: &summary,
+        };
+        let text = serde_json::to_string_pretty(&document).map_err(|error| EffError::Json {
+            path: None,
+            source: error,
+        })?;
+        println!("{}", text);
+    } else {
+        println!("File: {}", file.display());
+        println!("{}", summary);
+    }
     Ok(())
 }
+
+// regrava o arquivo na outra ordem de bytes; source None = detectar pelo cabecalho,
+// no Windows e no macOS "core.effblob" e "core.EFFBLOB" sao o mesmo arquivo; o caminho
+// canonico so existe quando o arquivo existe, senao nao tem como ser o mesmo
+fn same_file(a: &Path, b: &Path) -> bool {
+    match (a.canonicalize(), b.canonicalize()) {
+        (Ok(a), Ok(b)) => a.to_string_lossy().eq_ignore_ascii_case(&b.to_string_lossy()),
+        _ => a == b,
+    }
+}
+
+// target None = a ordem de platform, ou a contraria a do arquivo; output pode ser uma pasta
+// None = a saida seria o proprio arquivo, que nao é sobrescrito
+#[allow(clippy::too_many_arguments)]
+fn convert(
+    file: &Path,
+    output: Option<&Path>,
+    source: Option<Endian<()>>,
+    target: Option<Endian<()>>,
+    platform: Option<Platform>,
+    validate: bool,
+    copy_raw: bool,
+    log: &mut Vec<String>,
+) -> Result<Option<PathBuf>, EffError> {
+    let target = platform_endianness(platform, target)?;
+    let (x, source, _) = load(file, source)?;
+    let target = target.unwrap_or(if source.is_big() { Endian::Little(()) } else { Endian::Big(()) });
+    let name = with_suffix(&file.with_extension(""), platform.map_or_else(|| extension(&target), Platform::extension));
+    let output = match output {
+        Some(folder) if folder.is_dir() => folder.join(name.file_name().unwrap_or_default()),
+        Some(output) => output.to_path_buf(),
+        None => name,
+    };
+    if same_file(&output, file) {
+        if source.is_big() == target.is_big() {
+            log.push(format!("# Skipped: {} is already {} endian", file.display(), if source.is_big() { "big" } else { "little" }));
+        } else {
+            log.push(format!("# Skipped: the output would overwrite {}, use --output", file.display()));
+        }
+        return Ok(None);
+    }
+    check_raw_tables(&x, &source, &target, copy_raw, log)?;
+    write_file(&output, &compile(&x, &target, platform, validate, log)?)?;
+    Ok(Some(output))
+}
+
+fn banner() {
+    println!("# RE4_EFF_BLOB_RUST");
+    println!("# Tool By Zatarita");
+    println!("# Fork By JADERLINK");
+    println!("# Version {}", TOOL_VERSION);
+}
+
+// forma antiga dos argumentos, usada pelos arquivos .bat
+const LEGACY_FLAGS: [&str; 16] = [
+    "-e", "-eLittle", "-eBig", "-b", "-bLittle", "-bBig", "-v", "-vLittle", "-vBig", "-j", "-jb", "-jbLittle",
+    "-jbBig", "-csv", "-csvb", "-schema",
+];
+
+// None = quantidade errada de argumentos
+fn legacy(args: &[&str], log: &mut Vec<String>) -> Option<Result<bool, EffError>> {
+    let little = Some(Endian::Little(()));
+    let big = Some(Endian::Big(()));
+    let done = |result: Result<(), EffError>| result.map(|_| true);
+    let path = Path::new;
+
+    Some(match *args {
+        ["-e", file, out] => done(extract(path(file), path(out), None, None)), //extracao, detecta a ordem
+        ["-eLittle", file, out] => done(extract(path(file), path(out), little, None)), //extracao little
+        ["-eBig", file, out] => done(extract(path(file), path(out), big, None)), //extracao big
+        ["-b", file, out] => done(repack(path(file), Some(path(out)), None, None, true, false, log).map(drop)), //repack, ordem do .EFFBLOBTXT
+        ["-bLittle", file, out] => done(repack(path(file), Some(path(out)), little, None, true, false, log).map(drop)), //repack little
+        ["-bBig", file, out] => done(repack(path(file), Some(path(out)), big, None, true, false, log).map(drop)), //repack big
+        ["-v", file] => verify(path(file), None, log), //verificacao, detecta a ordem
+        ["-vLittle", file] => verify(path(file), little, log), //verificacao little
+        ["-vBig", file] => verify(path(file), big, log), //verificacao big
+        ["-j", file, out] => done(extract_json(path(file), path(out), None, None)), //extracao para json
+        ["-jb", file, out] => done(repack_json(path(file), Some(path(out)), None, None, true, log).map(drop)), //repack do json, ordem pela extensao
+        ["-jbLittle", file, out] => done(repack_json(path(file), Some(path(out)), little, None, true, log).map(drop)),
+        ["-jbBig", file, out] => done(repack_json(path(file), Some(path(out)), big, None, true, log).map(drop)),
+        ["-csv", file, out] => done(extract_csv(path(file), path(out), None, None)), //planilhas dos efeitos
+        ["-csvb", file, csv, out] => done(repack_csv(path(file), path(csv), path(out), true, log)), //aplica as planilhas
+        ["-schema", out] => done(write_schema(path(out))), //json schema
+        _ => return None,
+    })
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().collect();
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+
+    if !args.get(1).is_some_and(|flag| LEGACY_FLAGS.contains(flag)) {
+        return cli::run();
+    }
+
+    banner();
+    let mut log = Vec::new();
+    let result = legacy(&args[1..], &mut log);
+    for line in log {
+        println!("{}", line);
+    }
+    match result {
+        Some(Ok(true)) => {
+            println!("# Finished!!!");
+            ExitCode::SUCCESS
+        }
+        Some(Ok(false)) => ExitCode::FAILURE,
+        Some(Err(error)) => {
+            eprintln!("# Error: {}", error);
+            ExitCode::FAILURE
+        }
+        None => {
+            eprintln!("# Error: wrong number of arguments for {}, see --help", args[1]);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // pasta com "core.EFFBLOB" em little endian
+    fn folder(name: &str) -> PathBuf {
+        let root = env::temp_dir().join(format!("re4_effblob_convert_{}_{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(&root).unwrap();
+        std::fs::write(root.join("core.EFFBLOB"), Eff::default().compile(&Endian::Little(())).unwrap()).unwrap();
+        root
+    }
+
+    fn is_big(file: &Path) -> bool {
+        Eff::detect_endianness(&mut Cursor::new(std::fs::read(file).unwrap())).unwrap().is_big()
+    }
+
+    #[test]
+    fn convert_extension() {
+        let root = folder("extension");
+        let file = root.join("core.EFFBLOB");
+        let mut log = Vec::new();
+        let big = convert(&file, None, None, None, None, false, false, &mut log).unwrap();
+        let platform = convert(&file, None, None, None, Some(Platform::Wii), false, false, &mut log).unwrap();
+        let back = convert(&root.join("core.EFFBLOBBIG"), None, None, None, None, false, false, &mut log);
+        let (big_endian, little_endian) = (is_big(&root.join("core.EFFBLOBBIG")), is_big(&file));
+        let _ = std::fs::remove_dir_all(&root);
+
+        assert_eq!(big, Some(root.join("core.EFFBLOBBIG")));
+        assert_eq!(platform, Some(root.join("core.EFFBLOBBIG")));
+        assert_eq!(back.unwrap(), Some(file));
+        assert!(big_endian && !little_endian);
+    }
+
+    #[test]
+    fn convert_skips_the_source() {
+        let root = folder("skip");
+        let file = root.join("core.EFFBLOB");
+        let original = std::fs::read(&file).unwrap();
+        let mut log = Vec::new();
+        // mesma ordem: a saida seria o proprio arquivo
+        let same = convert(&file, None, None, Some(Endian::Little(())), None, false, false, &mut log).unwrap();
+        // ordem diferente, mas a saida indicada é o proprio arquivo
+        let output = convert(&file, Some(&file), None, None, None, false, false, &mut log).unwrap();
+        let unchanged = std::fs::read(&file).unwrap() == original;
+        let files = std::fs::read_dir(&root).unwrap().count();
+        let _ = std::fs::remove_dir_all(&root);
+
+        assert_eq!((same, output), (None, None));
+        assert!(unchanged);
+        assert_eq!(files, 1);
+        assert!(log[0].starts_with("# Skipped:") && log[0].ends_with("is already little endian"), "{:?}", log);
+        assert!(log[1].starts_with("# Skipped: the output would overwrite"), "{:?}", log);
+    }
+
+    #[test]
+    fn same_file_resolves_paths() {
+        let root = folder("case");
+        let file = root.join("core.EFFBLOB");
+        let relative = root.join(".").join("core.EFFBLOB");
+        let same = same_file(&relative, &file);
+        let missing = same_file(&root.join("core.EFFBLOBBIG"), &file);
+        let _ = std::fs::remove_dir_all(&root);
+
+        assert!(same);
+        assert!(!missing);
+    }
+
+    #[test]
+    fn convert_to_folder() {
+        let root = folder("output");
+        let output = root.join("out");
+        std::fs::create_dir_all(&output).unwrap();
+        let mut log = Vec::new();
+        let written = convert(&root.join("core.EFFBLOB"), Some(&output), None, None, None, false, false, &mut log);
+        let big_endian = is_big(&output.join("core.EFFBLOBBIG"));
+        let _ = std::fs::remove_dir_all(&root);
+
+        assert_eq!(written.unwrap(), Some(output.join("core.EFFBLOBBIG")));
+        assert!(big_endian);
+    }
+}